@@ -49,6 +49,7 @@ fn main() {
                 storage,
                 initial_rng_seed,
                 omniscient,
+                debug,
                 new_game,
             },
         col_encode_choice,
@@ -66,6 +67,7 @@ fn main() {
         storage,
         initial_rng_seed: InitialRngSeed::U64(initial_rng_seed),
         omniscient,
+        debug,
         new_game,
     });
     use ColEncodeChoice as C;
@@ -1,65 +1,268 @@
-use gridbugs::audio::{Audio as Sound, AudioHandle, AudioPlayer};
+use boat_journey_game::{GameEvent, SoundEvent};
+#[cfg(feature = "audio")]
+use std::time::Duration;
 
-use maplit::hashmap;
-use std::collections::HashMap;
+/// Something that can make noise. The game only describes what happened; backends decide what
+/// that sounds like.
+pub trait AudioBackend {
+    fn play_once(&mut self, sound_event: SoundEvent, volume: f32);
+    fn loop_music(&mut self, volume: f32);
+}
 
-pub type AppAudioPlayer = Option<AudioPlayer>;
-pub type AppHandle = Option<AudioHandle>;
+/// Makes no noise, but remembers what it was asked to play so audio can be exercised without a
+/// sound device
+#[derive(Default)]
+pub struct NullAudioBackend {
+    pub played: Vec<(SoundEvent, f32)>,
+    pub music_volume: Option<f32>,
+}
 
-#[derive(Clone, Copy, Hash, PartialEq, Eq, Debug)]
-pub enum Audio {}
+impl AudioBackend for NullAudioBackend {
+    fn play_once(&mut self, sound_event: SoundEvent, volume: f32) {
+        self.played.push((sound_event, volume));
+    }
 
-pub struct AudioState {
-    audio_player: AppAudioPlayer,
-    music_handle: AppHandle,
-    music_volume: f32,
-    music_volume_multiplier: f32,
+    fn loop_music(&mut self, volume: f32) {
+        self.music_volume = Some(volume);
+    }
 }
 
-impl AudioState {
-    pub fn new(audio_player: AppAudioPlayer) -> Self {
-        Self {
-            audio_player,
-            music_handle: None,
-            music_volume: 1.,
-            music_volume_multiplier: 1.,
+/// The melody of the music as semitones above A3 (`None` is a rest) and lengths in beats. It
+/// loops over a bass line which changes note every bar.
+#[cfg(feature = "audio")]
+const MELODY: &[(Option<i32>, u32)] = &[
+    (Some(0), 2),
+    (Some(3), 1),
+    (Some(5), 1),
+    (Some(7), 2),
+    (Some(5), 1),
+    (Some(3), 1),
+    (Some(0), 3),
+    (None, 1),
+    (Some(-2), 1),
+    (Some(0), 1),
+    (Some(3), 2),
+    (Some(5), 2),
+    (Some(3), 1),
+    (Some(0), 1),
+    (Some(7), 3),
+    (None, 1),
+    (Some(10), 2),
+    (Some(7), 1),
+    (Some(5), 1),
+    (Some(3), 2),
+    (Some(5), 1),
+    (Some(7), 1),
+    (Some(5), 3),
+    (None, 1),
+    (Some(3), 1),
+    (Some(0), 1),
+    (Some(-2), 2),
+    (Some(0), 4),
+];
+
+/// Semitones above A1 of the bass note held for each bar of 8 beats
+#[cfg(feature = "audio")]
+const BASS: &[i32] = &[0, 5, 3, 0];
+
+#[cfg(feature = "audio")]
+const BEAT: Duration = Duration::from_millis(300);
+
+#[cfg(feature = "audio")]
+const BEATS_PER_BAR: u32 = 8;
+
+/// Synthesizes a short tone for each sound, and a looping tune for music
+#[cfg(feature = "audio")]
+pub struct RodioAudioBackend {
+    _stream: rodio::OutputStream,
+    handle: rodio::OutputStreamHandle,
+    music_sink: Option<rodio::Sink>,
+}
+
+#[cfg(feature = "audio")]
+impl RodioAudioBackend {
+    /// Returns `None` if there is no audio device
+    pub fn new() -> Option<Self> {
+        match rodio::OutputStream::try_default() {
+            Ok((stream, handle)) => Some(Self {
+                _stream: stream,
+                handle,
+                music_sink: None,
+            }),
+            Err(e) => {
+                log::warn!("Failed to open audio device: {}", e);
+                None
+            }
         }
     }
 
-    pub fn play_once(&self, audio: Audio, volume: f32) {
-        log::info!("Playing audio {:?} at volume {:?}", audio, volume);
-        if let Some(sound) = self.audio_table.get(audio) {
-            if let Some(audio_player) = self.audio_player.as_ref() {
-                let handle = audio_player.play(&sound);
-                handle.set_volume(volume);
-                handle.background();
-            }
+    /// Frequency in Hz and duration in milliseconds of the tone played for each sound
+    fn tone(sound_event: SoundEvent) -> (f32, u64) {
+        match sound_event {
+            SoundEvent::Engine => (80., 120),
+            SoundEvent::Door => (220., 60),
+            SoundEvent::Ghost => (660., 400),
+            SoundEvent::BeastHit => (110., 200),
+            SoundEvent::Pickup => (880., 80),
+            SoundEvent::Blink => (1320., 150),
+            SoundEvent::Blast => (55., 500),
+        }
+    }
+
+    /// Frequency in Hz of the note `semitones` above `base`
+    fn note_frequency(base: f32, semitones: i32) -> f32 {
+        base * 2f32.powf(semitones as f32 / 12.)
+    }
+
+    /// A plucked note which fades out over its duration
+    fn note(frequency: f32, duration: Duration, amplitude: f32) -> impl rodio::Source<Item = f32> {
+        use rodio::Source;
+        let mut note = rodio::source::SineWave::new(frequency).take_duration(duration);
+        note.set_filter_fadeout();
+        note.amplify(amplitude)
+    }
+
+    fn music() -> impl rodio::Source<Item = f32> + Send {
+        use rodio::Source;
+        let melody = rodio::source::from_iter(MELODY.iter().map(|&(semitones, beats)| {
+            let (frequency, amplitude) = match semitones {
+                Some(semitones) => (Self::note_frequency(220., semitones), 0.5),
+                None => (220., 0.),
+            };
+            Self::note(frequency, BEAT * beats, amplitude)
+        }));
+        let bass = rodio::source::from_iter(BASS.iter().map(|&semitones| {
+            Self::note(
+                Self::note_frequency(55., semitones),
+                BEAT * BEATS_PER_BAR,
+                0.3,
+            )
+        }));
+        melody.mix(bass).repeat_infinite()
+    }
+}
+
+#[cfg(feature = "audio")]
+impl AudioBackend for RodioAudioBackend {
+    fn play_once(&mut self, sound_event: SoundEvent, volume: f32) {
+        use rodio::Source;
+        let (frequency, millis) = Self::tone(sound_event);
+        let source = rodio::source::SineWave::new(frequency)
+            .take_duration(Duration::from_millis(millis))
+            .amplify(volume);
+        if let Err(e) = self.handle.play_raw(source) {
+            log::error!("Failed to play sound: {}", e);
         }
     }
 
-    pub fn loop_music(&mut self, audio: Audio, volume: f32) {
-        log::info!("Looping audio {:?} at volume {:?}", audio, volume);
-        if let Some(sound) = self.audio_table.get(audio) {
-            if let Some(audio_player) = self.audio_player.as_ref() {
-                let handle = audio_player.play_loop(&sound);
-                handle.set_volume(volume * self.music_volume_multiplier);
-                self.music_handle = Some(handle);
-                self.music_volume = volume;
+    fn loop_music(&mut self, volume: f32) {
+        match rodio::Sink::try_new(&self.handle) {
+            Ok(sink) => {
+                sink.append(Self::music());
+                sink.set_volume(volume);
+                self.music_sink = Some(sink);
             }
+            Err(e) => log::error!("Failed to play music: {}", e),
         }
     }
+}
+
+pub struct AudioState {
+    backend: Box<dyn AudioBackend>,
+    sfx_volume: f32,
+}
+
+impl AudioState {
+    pub fn new(backend: Box<dyn AudioBackend>, music_volume: f32, sfx_volume: f32) -> Self {
+        let mut backend = backend;
+        backend.loop_music(music_volume);
+        Self {
+            backend,
+            sfx_volume,
+        }
+    }
+
+    /// Use the audio device if there is one, otherwise stay silent
+    #[cfg(feature = "audio")]
+    pub fn new_default(music_volume: f32, sfx_volume: f32) -> Self {
+        let backend: Box<dyn AudioBackend> = match RodioAudioBackend::new() {
+            Some(backend) => Box::new(backend),
+            None => Box::new(NullAudioBackend::default()),
+        };
+        Self::new(backend, music_volume, sfx_volume)
+    }
+
+    /// Stay silent when built without audio
+    #[cfg(not(feature = "audio"))]
+    pub fn new_default(music_volume: f32, sfx_volume: f32) -> Self {
+        Self::new(
+            Box::new(NullAudioBackend::default()),
+            music_volume,
+            sfx_volume,
+        )
+    }
+
+    /// Play the sounds made by things that happened in the game
+    pub fn play_game_events(&mut self, events: &[GameEvent]) {
+        self.play_sounds(events.iter().filter_map(|event| event.sound_event()));
+    }
 
-    pub fn set_music_volume(&mut self, volume: f32) {
-        self.music_volume = volume;
-        if let Some(music_handle) = self.music_handle.as_mut() {
-            music_handle.set_volume(volume * self.music_volume_multiplier);
+    pub fn play_sounds(&mut self, sound_events: impl IntoIterator<Item = SoundEvent>) {
+        for sound_event in sound_events {
+            log::info!("Playing {:?} at volume {:?}", sound_event, self.sfx_volume);
+            self.backend.play_once(sound_event, self.sfx_volume);
         }
     }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use boat_journey_game::{DamageSource, Junk};
+    use std::{cell::RefCell, rc::Rc};
+
+    /// Shares what it's asked to play with the test after being boxed up in an `AudioState`
+    #[derive(Clone, Default)]
+    struct RecordingAudioBackend(Rc<RefCell<NullAudioBackend>>);
 
-    pub fn set_music_volume_multiplier(&mut self, music_volume_multiplier: f32) {
-        self.music_volume_multiplier = music_volume_multiplier;
-        if let Some(music_handle) = self.music_handle.as_mut() {
-            music_handle.set_volume(self.music_volume * self.music_volume_multiplier);
+    impl AudioBackend for RecordingAudioBackend {
+        fn play_once(&mut self, sound_event: SoundEvent, volume: f32) {
+            self.0.borrow_mut().play_once(sound_event, volume);
         }
+
+        fn loop_music(&mut self, volume: f32) {
+            self.0.borrow_mut().loop_music(volume);
+        }
+    }
+
+    #[test]
+    fn music_starts_at_music_volume() {
+        let backend = RecordingAudioBackend::default();
+        let _audio_state = AudioState::new(Box::new(backend.clone()), 0.2, 0.5);
+        assert_eq!(backend.0.borrow().music_volume, Some(0.2));
+        assert!(backend.0.borrow().played.is_empty());
+    }
+
+    #[test]
+    fn game_events_play_their_sounds() {
+        let backend = RecordingAudioBackend::default();
+        let mut audio_state = AudioState::new(Box::new(backend.clone()), 0.2, 0.5);
+        audio_state.play_game_events(&[
+            GameEvent::DoorOpened,
+            GameEvent::Healed,
+            GameEvent::DamageTaken(DamageSource::Ghost),
+            GameEvent::DayStarted(2),
+            GameEvent::ItemPickedUp(Junk::VinylRecord),
+            GameEvent::BoatMoved,
+        ]);
+        assert_eq!(
+            backend.0.borrow().played,
+            vec![
+                (SoundEvent::Door, 0.5),
+                (SoundEvent::Ghost, 0.5),
+                (SoundEvent::Pickup, 0.5),
+                (SoundEvent::Engine, 0.5),
+            ]
+        );
     }
 }
@@ -0,0 +1,102 @@
+use crate::game_loop::{AppCF, State};
+use boat_journey_game::witness;
+use chargrid::{
+    control_flow::*,
+    input::KeyboardInput,
+    prelude::*,
+    text::{StyledString, Text},
+};
+
+/// Opens and closes the developer console when debugging is enabled
+pub const CONSOLE_KEY: KeyboardInput = KeyboardInput::Char('`');
+
+/// Lines of output kept in the console
+pub const CONSOLE_LOG_CAPACITY: usize = 100;
+
+const WIDTH: u32 = 72;
+const HEIGHT: u32 = 24;
+
+/// Debug commands typed one line at a time, with the output of previous commands above
+struct ConsoleComponent {
+    line: String,
+    /// Taken when the console is closed
+    running: Option<witness::Running>,
+}
+
+impl Component for ConsoleComponent {
+    type Output = Option<witness::Running>;
+    type State = State;
+
+    fn render(&self, state: &Self::State, ctx: Ctx, fb: &mut FrameBuffer) {
+        let num_lines = HEIGHT as usize - 4;
+        if let Some(game) = state.game() {
+            let player_coord = game.player_coord();
+            let header = StyledString {
+                string: format!(
+                    "Player at ({}, {}). Type \"help\" for commands.",
+                    player_coord.x, player_coord.y
+                ),
+                style: Style::plain_text().with_foreground(Rgba32::new_grey(127)),
+            };
+            header.render(&(), ctx, fb);
+        }
+        let log = state.console_log();
+        for (i, line) in log.iter().rev().take(num_lines).enumerate() {
+            let styled_string = StyledString {
+                string: line.clone(),
+                style: Style::plain_text(),
+            };
+            styled_string.render(&(), ctx.add_y(num_lines as i32 - i as i32), fb);
+        }
+        let prompt = vec![
+            StyledString {
+                string: format!("> {}", self.line),
+                style: Style::plain_text().with_bold(true),
+            },
+            StyledString {
+                string: format!("_"),
+                style: Style::plain_text().with_foreground(Rgba32::new_grey(127)),
+            },
+        ];
+        Text::new(prompt).render(&(), ctx.add_y(HEIGHT as i32 - 2), fb);
+        let footer = StyledString {
+            string: format!("Enter: run command, Escape: close"),
+            style: Style::plain_text().with_foreground(Rgba32::new_grey(127)),
+        };
+        footer.render(&(), ctx.add_y(HEIGHT as i32 - 1), fb);
+    }
+
+    fn update(&mut self, state: &mut Self::State, _ctx: Ctx, event: Event) -> Self::Output {
+        use chargrid::input::*;
+        if let Event::Input(Input::Keyboard(key)) = event {
+            match key {
+                keys::ESCAPE | CONSOLE_KEY => return self.running.take(),
+                keys::RETURN => {
+                    let line = std::mem::take(&mut self.line);
+                    if !line.trim().is_empty() {
+                        if let Some(running) = self.running.take() {
+                            self.running = Some(state.run_console_command(&line, running));
+                        }
+                    }
+                }
+                keys::BACKSPACE => {
+                    self.line.pop();
+                }
+                KeyboardInput::Char(ch) if !ch.is_control() => self.line.push(ch),
+                _ => (),
+            }
+        }
+        None
+    }
+
+    fn size(&self, _state: &Self::State, _ctx: Ctx) -> Size {
+        Size::new(WIDTH, HEIGHT)
+    }
+}
+
+pub fn console(running: witness::Running) -> AppCF<witness::Running> {
+    cf(ConsoleComponent {
+        line: String::new(),
+        running: Some(running),
+    })
+}
@@ -1,48 +1,180 @@
-use chargrid::input::{Input, KeyboardInput};
+use chargrid::{
+    input::{Input, KeyboardInput, MouseButton, MouseInput},
+    prelude::Coord,
+};
 use direction::CardinalDirection;
 use maplit::btreemap;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AppInput {
     Direction(CardinalDirection),
     Wait,
     DriveToggle,
     Ability(u8),
+    Examine,
+    WorldMap,
+    Explore,
+    Autopilot,
+    /// The left mouse button was pressed at this screen coordinate
+    Click(Coord),
 }
 
-#[derive(Serialize, Deserialize)]
+impl AppInput {
+    /// Every input that can be bound to a key
+    pub fn all() -> Vec<Self> {
+        let mut all = vec![
+            Self::Direction(CardinalDirection::North),
+            Self::Direction(CardinalDirection::South),
+            Self::Direction(CardinalDirection::West),
+            Self::Direction(CardinalDirection::East),
+            Self::Wait,
+            Self::DriveToggle,
+            Self::Examine,
+            Self::WorldMap,
+            Self::Explore,
+            Self::Autopilot,
+        ];
+        all.extend((1..=9).map(Self::Ability));
+        all
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Direction(CardinalDirection::North) => format!("Walk North / Forward"),
+            Self::Direction(CardinalDirection::South) => format!("Walk South / Backward"),
+            Self::Direction(CardinalDirection::West) => format!("Walk West / Turn Left"),
+            Self::Direction(CardinalDirection::East) => format!("Walk East / Turn Right"),
+            Self::Wait => format!("Wait"),
+            Self::DriveToggle => format!("Drive / Leave Boat"),
+            Self::Ability(i) => format!("Ability {i}"),
+            Self::Examine => format!("Examine"),
+            Self::WorldMap => format!("World Map"),
+            Self::Explore => format!("Auto-explore"),
+            Self::Autopilot => format!("Autopilot"),
+            Self::Click(_) => format!("Click"),
+        }
+    }
+}
+
+pub fn key_name(key: KeyboardInput) -> String {
+    match key {
+        KeyboardInput::Char(' ') => format!("Space"),
+        KeyboardInput::Char(c) => format!("{c}"),
+        KeyboardInput::Up => format!("Up"),
+        KeyboardInput::Down => format!("Down"),
+        KeyboardInput::Left => format!("Left"),
+        KeyboardInput::Right => format!("Right"),
+        other => format!("{:?}", other),
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Controls {
     keys: BTreeMap<KeyboardInput, AppInput>,
 }
 
+/// The arrow keys walk, and everything else has a single key
+fn default_keys() -> BTreeMap<KeyboardInput, AppInput> {
+    btreemap![
+        KeyboardInput::Left => AppInput::Direction(CardinalDirection::West),
+        KeyboardInput::Right => AppInput::Direction(CardinalDirection::East),
+        KeyboardInput::Up => AppInput::Direction(CardinalDirection::North),
+        KeyboardInput::Down => AppInput::Direction(CardinalDirection::South),
+        KeyboardInput::Char(' ') => AppInput::Wait,
+        KeyboardInput::Char('e') => AppInput::DriveToggle,
+        KeyboardInput::Char('x') => AppInput::Examine,
+        KeyboardInput::Char('m') => AppInput::WorldMap,
+        KeyboardInput::Char('o') => AppInput::Explore,
+        KeyboardInput::Char('p') => AppInput::Autopilot,
+        KeyboardInput::Char('1') => AppInput::Ability(1),
+        KeyboardInput::Char('2') => AppInput::Ability(2),
+        KeyboardInput::Char('3') => AppInput::Ability(3),
+        KeyboardInput::Char('4') => AppInput::Ability(4),
+        KeyboardInput::Char('5') => AppInput::Ability(5),
+        KeyboardInput::Char('6') => AppInput::Ability(6),
+        KeyboardInput::Char('7') => AppInput::Ability(7),
+        KeyboardInput::Char('8') => AppInput::Ability(8),
+        KeyboardInput::Char('9') => AppInput::Ability(9),
+    ]
+}
+
 impl Default for Controls {
     fn default() -> Self {
-        let keys = btreemap![
-            KeyboardInput::Left => AppInput::Direction(CardinalDirection::West),
-            KeyboardInput::Right => AppInput::Direction(CardinalDirection::East),
-            KeyboardInput::Up => AppInput::Direction(CardinalDirection::North),
-            KeyboardInput::Down => AppInput::Direction(CardinalDirection::South),
-            KeyboardInput::Char(' ') => AppInput::Wait,
-            KeyboardInput::Char('e') => AppInput::DriveToggle,
-            KeyboardInput::Char('1') => AppInput::Ability(1),
-            KeyboardInput::Char('2') => AppInput::Ability(2),
-            KeyboardInput::Char('3') => AppInput::Ability(3),
-            KeyboardInput::Char('4') => AppInput::Ability(4),
-            KeyboardInput::Char('5') => AppInput::Ability(5),
-            KeyboardInput::Char('6') => AppInput::Ability(6),
-            KeyboardInput::Char('7') => AppInput::Ability(7),
-            KeyboardInput::Char('8') => AppInput::Ability(8),
-            KeyboardInput::Char('9') => AppInput::Ability(9),
-        ];
-        Self { keys }
+        Self {
+            keys: default_keys(),
+        }
     }
 }
+
 impl Controls {
+    /// Walk with hjkl as well as the arrow keys
+    pub fn vi_keys() -> Self {
+        let mut keys = default_keys();
+        keys.extend(btreemap![
+            KeyboardInput::Char('h') => AppInput::Direction(CardinalDirection::West),
+            KeyboardInput::Char('l') => AppInput::Direction(CardinalDirection::East),
+            KeyboardInput::Char('k') => AppInput::Direction(CardinalDirection::North),
+            KeyboardInput::Char('j') => AppInput::Direction(CardinalDirection::South),
+        ]);
+        Self { keys }
+    }
+
+    /// Walk with wasd as well as the arrow keys
+    pub fn wasd() -> Self {
+        let mut keys = default_keys();
+        keys.extend(btreemap![
+            KeyboardInput::Char('a') => AppInput::Direction(CardinalDirection::West),
+            KeyboardInput::Char('d') => AppInput::Direction(CardinalDirection::East),
+            KeyboardInput::Char('w') => AppInput::Direction(CardinalDirection::North),
+            KeyboardInput::Char('s') => AppInput::Direction(CardinalDirection::South),
+        ]);
+        Self { keys }
+    }
+
+    /// Bind inputs which have no keys (e.g. inputs added since the controls were saved) to their
+    /// default keys, unless those keys are already bound to something else
+    pub fn add_missing_defaults(&mut self) {
+        for (key, app_input) in default_keys() {
+            if self.keys.values().all(|bound| *bound != app_input) {
+                if self.keys.contains_key(&key) {
+                    log::warn!(
+                        "Leaving {} unbound as its default key is in use",
+                        app_input.name()
+                    );
+                } else {
+                    self.keys.insert(key, app_input);
+                }
+            }
+        }
+    }
+
+    pub fn keys_for(&self, app_input: &AppInput) -> Vec<KeyboardInput> {
+        self.keys
+            .iter()
+            .filter(|(_, bound)| *bound == app_input)
+            .map(|(&key, _)| key)
+            .collect()
+    }
+
+    pub fn input_for(&self, key: KeyboardInput) -> Option<&AppInput> {
+        self.keys.get(&key)
+    }
+
+    /// Replace all the keys bound to an input with a single key
+    pub fn rebind(&mut self, app_input: AppInput, key: KeyboardInput) {
+        self.keys.retain(|_, bound| *bound != app_input);
+        self.keys.insert(key, app_input);
+    }
+
     pub fn get(&self, input: Input) -> Option<AppInput> {
         match input {
             Input::Keyboard(keyboard_input) => self.keys.get(&keyboard_input).cloned(),
+            Input::Mouse(MouseInput::MousePress {
+                button: MouseButton::Left,
+                coord,
+            }) => Some(AppInput::Click(coord)),
             Input::Mouse(_) => None,
         }
     }
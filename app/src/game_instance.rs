@@ -1,7 +1,9 @@
-use crate::{colour, mist::Mist};
+use crate::{colour, game_loop::TravelError, mist::Mist, save_slot::SaveMetadata};
 use boat_journey_game::{
+    replay::Replay,
     witness::{self, Game, RunningGame},
-    CellVisibility, Config, Layer, Meter, Tile, Victory,
+    ActionError, BeastBehaviour, CardinalDirection, CellVisibility, Config, Layer, Meter, Tile,
+    Victory,
 };
 use chargrid::{prelude::*, text};
 use rand::Rng;
@@ -40,10 +42,85 @@ impl Tint for NightTint {
     }
 }
 
+/// What the cursor is being used for while it's shown over the game
+#[derive(Clone, Copy)]
+pub enum CursorMode {
+    Aim,
+    /// Describe the contents of the game coordinate under the cursor
+    Examine(Coord),
+}
+
+/// Usage of the developer console command which toggles debug overlays
+pub const DEBUG_OVERLAY_USAGE: &str = "overlay distance|vision|areas|ghosts";
+
+/// Debugging aids drawn over the game, toggled from the developer console
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DebugOverlays {
+    /// The distance map beasts use to choose where to move
+    pub beast_distances: bool,
+    /// The edge of the area the player can see, which grows with the telescope
+    pub vision: bool,
+    /// The edges of the area from which beasts react to the player, which changes with fear and
+    /// sneak, and of the range of each passenger's aimed ability
+    pub effect_areas: bool,
+    /// Where ghosts appear at night
+    pub ghost_spawn: bool,
+}
+
+impl DebugOverlays {
+    /// Toggle an overlay by the name used in `DEBUG_OVERLAY_USAGE`, returning whether it's now
+    /// shown, or `None` if there's no overlay with that name
+    pub fn toggle(&mut self, name: &str) -> Option<bool> {
+        let overlay = match name {
+            "distance" => &mut self.beast_distances,
+            "vision" => &mut self.vision,
+            "areas" => &mut self.effect_areas,
+            "ghosts" => &mut self.ghost_spawn,
+            _ => return None,
+        };
+        *overlay = !*overlay;
+        Some(*overlay)
+    }
+}
+
+/// Why the most recent action was rejected, either by the game or by the app while travelling
+#[derive(Debug, Clone, Copy)]
+pub enum AppActionError {
+    Game(ActionError),
+    Travel(TravelError),
+}
+
+impl AppActionError {
+    pub fn message(self) -> String {
+        match self {
+            Self::Game(error) => error.message(),
+            Self::Travel(error) => error.message(),
+        }
+    }
+}
+
+impl From<ActionError> for AppActionError {
+    fn from(error: ActionError) -> Self {
+        Self::Game(error)
+    }
+}
+
+impl From<TravelError> for AppActionError {
+    fn from(error: TravelError) -> Self {
+        Self::Travel(error)
+    }
+}
+
 pub struct GameInstance {
     pub game: Game,
     pub mist: Mist,
     pub fade_state: FadeState,
+    /// Why the most recent action was rejected, if it was
+    pub action_error: Option<AppActionError>,
+    /// News from the app rather than the game, such as the autopilot arriving, shown until the
+    /// next input
+    pub notice: Option<String>,
+    pub debug_overlays: DebugOverlays,
 }
 
 impl GameInstance {
@@ -59,23 +136,32 @@ impl GameInstance {
                 game,
                 mist,
                 fade_state: FadeState::new(),
+                action_error: None,
+                notice: None,
+                debug_overlays: DebugOverlays::default(),
             },
             running,
         )
     }
 
-    pub fn into_storable(self, running: witness::Running) -> GameInstanceStorable {
+    /// Separate the game into the parts that are saved together and its replay, which is
+    /// stored on its own
+    pub fn into_storable(self, running: witness::Running) -> (GameInstanceStorable, Replay) {
         let Self {
             game,
             mist,
             fade_state,
+            ..
         } = self;
-        let running_game = game.into_running_game(running);
-        GameInstanceStorable {
-            running_game,
-            mist,
-            fade_state,
-        }
+        let (running_game, replay) = game.into_running_game(running);
+        (
+            GameInstanceStorable {
+                running_game,
+                mist,
+                fade_state,
+            },
+            replay,
+        )
     }
 
     fn layer_to_depth(layer: Layer) -> i8 {
@@ -195,6 +281,7 @@ impl GameInstance {
                 }
             }
             Tile::Water2 => ' ',
+            Tile::Shallows => ',',
             Tile::Floor => '.',
             Tile::BurntFloor => {
                 return RenderCell {
@@ -292,6 +379,8 @@ impl GameInstance {
 
     pub fn render_game(&self, ctx: Ctx, fb: &mut FrameBuffer) -> HashSet<Tile> {
         let mut tiles = HashSet::new();
+        // overlays are drawn without the night tint so their colours can be told apart
+        let overlay_ctx = ctx;
         let ctx = if self.game.inner_ref().is_player_outside_at_night() {
             ctx.with_tint(&NightTint)
         } else {
@@ -363,9 +452,95 @@ impl GameInstance {
                 }
             }
         }
+        self.render_debug_overlays(overlay_ctx, fb, centre_coord_delta);
         tiles
     }
 
+    fn render_debug_overlays(&self, ctx: Ctx, fb: &mut FrameBuffer, centre_coord_delta: Coord) {
+        let overlays = self.debug_overlays;
+        let game = self.game.inner_ref();
+        let player_coord = game.player_coord();
+        let vision_distance_squared = game.vision_distance_squared() as i32;
+        let is_visible_distance = |coord: Coord| {
+            let delta = coord - player_coord;
+            delta.x * delta.x + delta.y * delta.y <= vision_distance_squared
+        };
+        let (beast_colour, beast_range) = match game.beast_behaviour() {
+            BeastBehaviour::Approach { range } => (Rgb24::new(255, 63, 63), Some(range)),
+            BeastBehaviour::Flee { range } => (Rgb24::new(63, 187, 255), Some(range)),
+            BeastBehaviour::Ignore => (Rgb24::new_grey(127), None),
+        };
+        let in_beast_range = |coord: Coord| match (beast_range, game.beast_distance(coord)) {
+            (Some(range), Some(distance)) => distance <= range,
+            _ => false,
+        };
+        // aimed abilities are marked with the first letter of their name
+        let ability_ranges = game
+            .passengers()
+            .iter()
+            .filter_map(|npc| {
+                let ability = npc.ability();
+                let range = ability.range()?;
+                Some((ability.name().chars().next()?, range as i32))
+            })
+            .collect::<Vec<_>>();
+        let depth = 10;
+        let set_background = |fb: &mut FrameBuffer, coord, background| {
+            let render_cell = RenderCell::default().with_background(background);
+            fb.set_cell_relative_to_ctx(ctx, coord, depth, render_cell);
+        };
+        for coord in ctx.bounding_box.size().coord_iter_row_major() {
+            let game_coord = coord + centre_coord_delta;
+            let delta = game_coord - player_coord;
+            if overlays.ghost_spawn {
+                let distance = ((delta.x * delta.x + delta.y * delta.y) as f64).sqrt();
+                if (distance - game.ghost_spawn_distance()).abs() < 0.5 {
+                    set_background(fb, coord, Rgba32::new(127, 0, 127, 255));
+                }
+            }
+            if overlays.effect_areas {
+                // beasts follow the distance map, so their range is measured in steps along it
+                if in_beast_range(game_coord)
+                    && CardinalDirection::all().any(|d| !in_beast_range(game_coord + d.coord()))
+                {
+                    set_background(
+                        fb,
+                        coord,
+                        beast_colour.saturating_scalar_mul_div(1, 2).to_rgba32(255),
+                    );
+                }
+                let in_range = |delta: Coord, range: i32| {
+                    delta.x * delta.x + delta.y * delta.y <= range * range
+                };
+                for &(character, range) in &ability_ranges {
+                    if in_range(delta, range)
+                        && CardinalDirection::all().any(|d| !in_range(delta + d.coord(), range))
+                    {
+                        let render_cell = RenderCell::default()
+                            .with_character(character)
+                            .with_foreground(Rgba32::new(0, 255, 127, 255));
+                        fb.set_cell_relative_to_ctx(ctx, coord, depth, render_cell);
+                    }
+                }
+            }
+            if overlays.vision
+                && is_visible_distance(game_coord)
+                && CardinalDirection::all().any(|d| !is_visible_distance(game_coord + d.coord()))
+            {
+                set_background(fb, coord, Rgba32::new(127, 127, 0, 255));
+            }
+            if overlays.beast_distances {
+                if let Some(distance) = game.beast_distance(game_coord) {
+                    let character = std::char::from_digit(distance.min(35), 36).unwrap();
+                    let render_cell = RenderCell::default()
+                        .with_character(character)
+                        .with_foreground(beast_colour.to_rgba32(255));
+                    fb.set_cell_relative_to_ctx(ctx, coord, depth, render_cell);
+                }
+            }
+        }
+    }
+
     fn render_hints(&self, ctx: Ctx, fb: &mut FrameBuffer, tiles: &HashSet<Tile>) {
         use text::*;
         let stats = self.game.inner_ref().stats();
@@ -422,6 +597,12 @@ impl GameInstance {
                 style: Style::plain_text(),
             });
         }
+        if stats.hull_integrity.current() == 1 {
+            hints.push(StyledString {
+                string: format!("The boat won't survive another collision!\n\n"),
+                style: Style::plain_text(),
+            });
+        }
         if stats.health.current() == 1 {
             hints.push(StyledString {
                 string: format!("You are barely clinging to consciousness...\n\n"),
@@ -468,6 +649,10 @@ impl GameInstance {
             string: format!("Day {}  ", self.game.inner_ref().current_day()),
             style: Style::plain_text().with_bold(true),
         };
+        let weather_text = StyledString {
+            string: format!("{}  ", self.game.inner_ref().weather().name()),
+            style: Style::plain_text().with_bold(true),
+        };
         fn meter_text(name: &str, meter: &Meter) -> Vec<StyledString> {
             vec![
                 StyledString {
@@ -481,11 +666,12 @@ impl GameInstance {
             ]
         }
         let text = vec![
-            vec![activity_text, day_text],
+            vec![activity_text, day_text, weather_text],
             meter_text("Health", &stats.health),
             meter_text("Fuel", &stats.fuel),
             meter_text("Light", &stats.day),
             meter_text("Junk", &stats.junk),
+            meter_text("Hull", &stats.hull_integrity),
         ];
         Text::new(text.concat()).render(&(), ctx, fb);
     }
@@ -494,6 +680,12 @@ impl GameInstance {
         use text::*;
         let max = 4;
         let mut messages: Vec<(usize, String)> = Vec::new();
+        if let Some(action_error) = self.action_error {
+            messages.push((1, action_error.message()));
+        }
+        if let Some(notice) = self.notice.as_ref() {
+            messages.push((1, notice.clone()));
+        }
         for m in self.game.inner_ref().messages().iter().rev() {
             if messages.len() >= max {
                 break;
@@ -536,7 +728,7 @@ impl GameInstance {
         for (i, &npc) in passengers.iter().enumerate() {
             let i = i + 1;
             let name = npc.name();
-            let ability_name = npc.ability_name();
+            let ability_name = npc.ability().name();
             let usage = game.npc_action(npc).unwrap();
             text_parts.push(StyledString {
                 string: format!("{i}. {name}\n"),
@@ -567,22 +759,9 @@ impl GameInstance {
             string: format!("Effects:\n\n"),
             style: Style::plain_text(),
         }];
-        let effects = game.effect_timeouts();
-        if effects.fear > 0 {
-            text_parts.push(StyledString {
-                string: format!("Fear: {}\n\n", effects.fear),
-                style: Style::plain_text().with_bold(true),
-            });
-        }
-        if effects.phase > 0 {
-            text_parts.push(StyledString {
-                string: format!("Phase: {}\n\n", effects.phase),
-                style: Style::plain_text().with_bold(true),
-            });
-        }
-        if effects.sneak > 0 {
+        for effect in game.effects().iter() {
             text_parts.push(StyledString {
-                string: format!("Sneak: {}\n\n", effects.sneak),
+                string: format!("{}: {}\n\n", effect.kind.name(), effect.remaining),
                 style: Style::plain_text().with_bold(true),
             });
         }
@@ -595,6 +774,26 @@ impl GameInstance {
         Text::new(text_parts).render(&(), ctx, fb);
     }
 
+    fn render_examine_hint(&self, ctx: Ctx, fb: &mut FrameBuffer, coord: Coord) {
+        use text::*;
+        let description = self.game.inner_ref().examine(coord);
+        let hints = vec![
+            StyledString {
+                string: format!("EXAMINING\n\n"),
+                style: Style::plain_text().with_bold(true),
+            },
+            StyledString {
+                string: format!("{description}\n\n"),
+                style: Style::plain_text(),
+            },
+            StyledString {
+                string: format!("Use the mouse or arrow keys to move the cursor.\n\nPress enter to travel here.\n\nPress escape to stop examining."),
+                style: Style::plain_text().with_bold(true),
+            },
+        ];
+        Text::new(hints).render(&(), ctx, fb);
+    }
+
     fn render_aim_hint(&self, ctx: Ctx, fb: &mut FrameBuffer) {
         use text::*;
         let s = "AIMING\n\nUse the mouse or arrow keys to move the cursor.\n\nPress enter or left mouse button to commit.\n\nPress escape to cancel.";
@@ -605,12 +804,13 @@ impl GameInstance {
         ss.render(&(), ctx, fb);
     }
 
-    pub fn render(&self, ctx: Ctx, fb: &mut FrameBuffer, aim_hint: bool) {
+    pub fn render(&self, ctx: Ctx, fb: &mut FrameBuffer, cursor_mode: Option<CursorMode>) {
         let tiles = self.render_game(ctx, fb);
-        if aim_hint {
-            self.render_aim_hint(ctx.add_xy(1, 1).add_depth(20), fb);
-        } else {
-            self.render_hints(ctx.add_xy(1, 1).add_depth(20), fb, &tiles);
+        let hint_ctx = ctx.add_xy(1, 1).add_depth(20);
+        match cursor_mode {
+            Some(CursorMode::Aim) => self.render_aim_hint(hint_ctx, fb),
+            Some(CursorMode::Examine(coord)) => self.render_examine_hint(hint_ctx, fb, coord),
+            None => self.render_hints(hint_ctx, fb, &tiles),
         }
         self.render_messages(
             ctx.add_xy(1, ctx.bounding_box.size().height() as i32 - 7)
@@ -643,20 +843,47 @@ pub struct GameInstanceStorable {
 }
 
 impl GameInstanceStorable {
-    pub fn into_game_instance(self) -> (GameInstance, witness::Running) {
+    pub fn game(&self) -> &boat_journey_game::Game {
+        self.running_game.game()
+    }
+
+    /// A summary of the game to store alongside it
+    pub fn metadata(&self, replay: &Replay) -> SaveMetadata {
+        SaveMetadata::new(self.running_game.game(), replay)
+    }
+
+    pub fn into_game_instance(
+        self,
+        replay: Replay,
+        config: &Config,
+    ) -> (GameInstance, witness::Running) {
         let Self {
             running_game,
             mist,
             fade_state,
         } = self;
-        let (game, running) = running_game.into_game();
+        let (game, running) = running_game.into_game(replay, config);
         (
             GameInstance {
                 game,
                 mist,
                 fade_state,
+                action_error: None,
+                notice: None,
+                debug_overlays: DebugOverlays::default(),
             },
             running,
         )
     }
 }
+
+#[cfg(test)]
+impl GameInstanceStorable {
+    /// A new game generated from `seed`, as it would be saved
+    pub fn new_for_test(seed: u64) -> (Self, Replay) {
+        use rand::SeedableRng;
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(seed);
+        let (instance, running) = GameInstance::new(&Config::default(), Vec::new(), &mut rng);
+        instance.into_storable(running)
+    }
+}
@@ -1,15 +1,22 @@
 use crate::{
-    controls::{AppInput, Controls},
-    game_instance::{GameInstance, GameInstanceStorable},
+    console::{self, CONSOLE_KEY, CONSOLE_LOG_CAPACITY},
+    controls::{self, AppInput, Controls},
+    game_instance::{CursorMode, GameInstance, GameInstanceStorable, DEBUG_OVERLAY_USAGE},
     image::Images,
-    text,
+    message_log,
+    save_format::{LoadGameError, SaveEnvelope},
+    save_slot::{SaveMetadata, SaveSlot},
+    storage_handle::StorageHandle,
+    text, world_map,
 };
 use boat_journey_game::{
+    replay::Replay,
     witness::{self, Witness},
-    Config as GameConfig, GameOverReason, MenuChoice as GameMenuChoice, Victory,
+    Autopilot, AutopilotDestination, AutopilotStop, CardinalDirection, Config as GameConfig,
+    DebugCommand, Game, GameOverReason, MenuChoice as GameMenuChoice, Victory, DEBUG_COMMANDS,
 };
 use chargrid::{self, border::BorderStyle, control_flow::*, menu, prelude::*};
-use general_storage_static::{self as storage, format, StaticStorage as Storage};
+use general_storage_static::{self as storage, format, Storage};
 use rand::{Rng, SeedableRng};
 use rand_isaac::Isaac64Rng;
 use serde::{Deserialize, Serialize};
@@ -39,6 +46,42 @@ impl Default for Config {
 pub type AppCF<T> = CF<Option<T>, GameLoopData>;
 pub type State = GameLoopData;
 
+/// The game is saved automatically after this many turns, as well as at the start of each day
+const AUTOSAVE_INTERVAL_TURNS: u64 = 100;
+
+/// How long to wait between steps when travelling without input
+const TRAVEL_STEP_INTERVAL: Duration = Duration::from_millis(80);
+
+/// Walking or driving which continues over several turns without further input
+enum Travel {
+    /// Walk to a remembered coordinate
+    Destination(Coord),
+    /// Walk towards the nearest unexplored cell until there are none left
+    Explore,
+    /// Drive the boat until the autopilot stops
+    Autopilot(Autopilot),
+}
+
+/// Why travel couldn't start or had to stop
+#[derive(Debug, Clone, Copy)]
+pub enum TravelError {
+    EnemyInView,
+    NoKnownPath,
+    NothingToExplore,
+    Autopilot(AutopilotStop),
+}
+
+impl TravelError {
+    pub fn message(self) -> String {
+        match self {
+            Self::EnemyInView => format!("You can't travel with enemies in view."),
+            Self::NoKnownPath => format!("You don't know a way there."),
+            Self::NothingToExplore => format!("There's nowhere left to explore."),
+            Self::Autopilot(stop) => stop.message(),
+        }
+    }
+}
+
 const MENU_BACKGROUND: Rgba32 = Rgba32::new_rgb(0, 0, 0);
 const MENU_FADE_SPEC: menu::identifier::fade_spec::FadeSpec = {
     use menu::identifier::fade_spec::*;
@@ -111,62 +154,238 @@ impl RngSeedSource {
 }
 
 pub struct AppStorage {
-    pub handle: Storage,
+    pub handle: StorageHandle,
+    /// Each save slot has its own key derived from this one
     pub save_game_key: String,
     pub config_key: String,
     pub controls_key: String,
+    /// Each save slot has its own key derived from this one
+    pub replay_key: String,
 }
 
 impl AppStorage {
-    const SAVE_GAME_STORAGE_FORMAT: format::Bincode = format::Bincode;
+    const SAVE_METADATA_STORAGE_FORMAT: format::JsonPretty = format::JsonPretty;
     const CONFIG_STORAGE_FORMAT: format::JsonPretty = format::JsonPretty;
     const CONTROLS_STORAGE_FORMAT: format::JsonPretty = format::JsonPretty;
+    const REPLAY_STORAGE_FORMAT: format::Bincode = format::Bincode;
 
-    fn save_game(&mut self, instance: &GameInstanceStorable) {
+    /// Save a game in a slot. Its replay is stored beside it rather than in the save, as it
+    /// grows for the whole run.
+    pub fn save_game(
+        &mut self,
+        slot: SaveSlot,
+        instance: &GameInstanceStorable,
+        replay: &Replay,
+        metadata: &SaveMetadata,
+    ) {
+        let bytes = match SaveEnvelope::new(instance).and_then(|envelope| envelope.to_bytes()) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                log::error!("Failed to format save file: {}", e);
+                return;
+            }
+        };
+        self.back_up_game(slot);
+        self.save_replay(slot, replay);
+        let result = self
+            .handle
+            .store_raw(slot.save_game_key(&self.save_game_key), bytes);
+        if let Err(e) = result {
+            use storage::StoreRawError;
+            match e {
+                StoreRawError::IoError(e) => {
+                    log::error!("Error while writing save data: {}", e)
+                }
+            }
+            return;
+        }
         let result = self.handle.store(
-            &self.save_game_key,
-            &instance,
-            Self::SAVE_GAME_STORAGE_FORMAT,
+            slot.metadata_key(&self.save_game_key),
+            &metadata,
+            Self::SAVE_METADATA_STORAGE_FORMAT,
         );
         if let Err(e) = result {
             use storage::{StoreError, StoreRawError};
             match e {
-                StoreError::FormatError(e) => log::error!("Failed to format save file: {}", e),
+                StoreError::FormatError(e) => log::error!("Failed to format save metadata: {}", e),
                 StoreError::Raw(e) => match e {
                     StoreRawError::IoError(e) => {
-                        log::error!("Error while writing save data: {}", e)
+                        log::error!("Error while writing save metadata: {}", e)
                     }
                 },
             }
         }
     }
 
-    fn load_game(&self) -> Option<GameInstanceStorable> {
-        let result = self.handle.load::<_, GameInstanceStorable, _>(
-            &self.save_game_key,
-            Self::SAVE_GAME_STORAGE_FORMAT,
+    /// Copy the game saved in a slot and its replay to the slot's backup, so it can still be
+    /// loaded if the save that's about to replace it is damaged
+    fn back_up_game(&mut self, slot: SaveSlot) {
+        let bytes = match self
+            .handle
+            .load_raw(slot.save_game_key(&self.save_game_key))
+        {
+            Ok(bytes) => bytes,
+            Err(_) => return,
+        };
+        // a damaged save must never replace a good backup
+        if !SaveEnvelope::is_loadable(&bytes) {
+            return;
+        }
+        let replay_bytes = match self.handle.load_raw(slot.replay_key(&self.replay_key)) {
+            Ok(bytes) => bytes,
+            Err(_) => return,
+        };
+        let result = self
+            .handle
+            .store_raw(slot.backup_key(&self.save_game_key), bytes)
+            .and_then(|()| {
+                self.handle
+                    .store_raw(slot.backup_key(&self.replay_key), replay_bytes)
+            });
+        if let Err(e) = result {
+            use storage::StoreRawError;
+            match e {
+                StoreRawError::IoError(e) => {
+                    log::error!("Error while writing save backup: {}", e)
+                }
+            }
+        }
+    }
+
+    /// Load the game saved in a slot and its replay, migrating the game from older versions of
+    /// the save format. If the save can't be loaded the backup of the previous save is loaded
+    /// instead. Returns `Ok(None)` if there's nothing saved in the slot.
+    pub fn load_game(
+        &self,
+        slot: SaveSlot,
+    ) -> Result<Option<(GameInstanceStorable, Replay)>, LoadGameError> {
+        let result = self.load_save_file(
+            slot.save_game_key(&self.save_game_key),
+            slot.replay_key(&self.replay_key),
+        );
+        if let Ok(Some(_)) = result {
+            return result;
+        }
+        match self.load_save_file(
+            slot.backup_key(&self.save_game_key),
+            slot.backup_key(&self.replay_key),
+        ) {
+            Ok(Some(save)) => {
+                log::warn!("Loaded the backup of {}", slot.name());
+                Ok(Some(save))
+            }
+            _ => result,
+        }
+    }
+
+    fn load_save_file(
+        &self,
+        key: String,
+        replay_key: String,
+    ) -> Result<Option<(GameInstanceStorable, Replay)>, LoadGameError> {
+        let bytes = match self.handle.load_raw(key) {
+            Err(e) => {
+                use storage::LoadRawError;
+                match e {
+                    LoadRawError::IoError(e) => {
+                        log::error!("Error while reading save data: {}", e)
+                    }
+                    LoadRawError::NoSuchKey => (),
+                }
+                return Ok(None);
+            }
+            Ok(bytes) => bytes,
+        };
+        let envelope = SaveEnvelope::from_bytes(bytes);
+        let version = envelope.version();
+        let instance = match envelope.into_instance() {
+            Ok(instance) => instance,
+            Err(e) => {
+                log::error!(
+                    "Failed to load save file (format version {}): {:?}",
+                    version,
+                    e
+                );
+                return Err(e);
+            }
+        };
+        let replay = self
+            .load_replay(replay_key)
+            .ok_or(LoadGameError::MissingReplay)?;
+        Ok(Some((instance, replay)))
+    }
+
+    /// The replay stored beside the game saved in a slot
+    pub fn load_slot_replay(&self, slot: SaveSlot) -> Option<Replay> {
+        self.load_replay(slot.replay_key(&self.replay_key))
+    }
+
+    fn load_replay(&self, key: String) -> Option<Replay> {
+        match self.handle.load(key, Self::REPLAY_STORAGE_FORMAT) {
+            Err(e) => {
+                use storage::{LoadError, LoadRawError};
+                match e {
+                    LoadError::FormatError(e) => log::error!("Failed to parse replay: {}", e),
+                    LoadError::Raw(e) => match e {
+                        LoadRawError::IoError(e) => {
+                            log::error!("Error while reading replay: {}", e)
+                        }
+                        LoadRawError::NoSuchKey => (),
+                    },
+                }
+                None
+            }
+            Ok(replay) => Some(replay),
+        }
+    }
+
+    /// The summary of the game saved in a slot, without loading the game itself
+    pub fn load_metadata(&self, slot: SaveSlot) -> Option<SaveMetadata> {
+        let result = self.handle.load::<_, SaveMetadata, _>(
+            slot.metadata_key(&self.save_game_key),
+            Self::SAVE_METADATA_STORAGE_FORMAT,
         );
         match result {
             Err(e) => {
                 use storage::{LoadError, LoadRawError};
                 match e {
-                    LoadError::FormatError(e) => log::error!("Failed to parse save file: {}", e),
+                    LoadError::FormatError(e) => {
+                        log::error!("Failed to parse save metadata: {}", e)
+                    }
                     LoadError::Raw(e) => match e {
                         LoadRawError::IoError(e) => {
-                            log::error!("Error while reading save data: {}", e)
+                            log::error!("Error while reading save metadata: {}", e)
                         }
                         LoadRawError::NoSuchKey => (),
                     },
                 }
                 None
             }
-            Ok(instance) => Some(instance),
+            Ok(metadata) => Some(metadata),
         }
     }
 
-    fn clear_game(&mut self) {
-        if self.handle.exists(&self.save_game_key) {
-            if let Err(e) = self.handle.remove(&self.save_game_key) {
+    fn has_saved_game(&self, slot: SaveSlot) -> bool {
+        self.handle.exists(slot.save_game_key(&self.save_game_key))
+            || self.handle.exists(slot.backup_key(&self.save_game_key))
+    }
+
+    /// The slot that was saved to most recently. Saves from before there were save slots have
+    /// no metadata, so they are only chosen if no other slot has been saved to.
+    fn most_recent_slot(&self) -> Option<SaveSlot> {
+        SaveSlot::all()
+            .filter_map(|slot| {
+                self.load_metadata(slot)
+                    .map(|metadata| (metadata.timestamp, slot))
+            })
+            .max_by_key(|&(timestamp, _)| timestamp)
+            .map(|(_, slot)| slot)
+            .or_else(|| SaveSlot::all().find(|&slot| self.has_saved_game(slot)))
+    }
+
+    fn remove(&mut self, key: String) {
+        if self.handle.exists(&key) {
+            if let Err(e) = self.handle.remove(&key) {
                 use storage::RemoveError;
                 match e {
                     RemoveError::IoError(e) => {
@@ -178,6 +397,40 @@ impl AppStorage {
         }
     }
 
+    /// Remove the game saved in a slot. Its replay is kept so it can still be attached to bug
+    /// reports.
+    fn clear_game(&mut self, slot: SaveSlot) {
+        self.remove(slot.save_game_key(&self.save_game_key));
+        self.remove(slot.backup_key(&self.save_game_key));
+        self.remove(slot.backup_key(&self.replay_key));
+        self.remove(slot.metadata_key(&self.save_game_key));
+    }
+
+    pub fn clear_all_games(&mut self) {
+        for slot in SaveSlot::all() {
+            self.clear_game(slot);
+        }
+    }
+
+    fn save_replay(&mut self, slot: SaveSlot, replay: &Replay) {
+        let result = self.handle.store(
+            slot.replay_key(&self.replay_key),
+            &replay,
+            Self::REPLAY_STORAGE_FORMAT,
+        );
+        if let Err(e) = result {
+            use storage::{StoreError, StoreRawError};
+            match e {
+                StoreError::FormatError(e) => log::error!("Failed to format replay: {}", e),
+                StoreError::Raw(e) => match e {
+                    StoreRawError::IoError(e) => {
+                        log::error!("Error while writing replay: {}", e)
+                    }
+                },
+            }
+        }
+    }
+
     fn save_config(&mut self, config: &Config) {
         let result = self
             .handle
@@ -254,7 +507,10 @@ impl AppStorage {
                 }
                 None
             }
-            Ok(instance) => Some(instance),
+            Ok(mut controls) => {
+                controls.add_missing_defaults();
+                Some(controls)
+            }
         }
     }
 }
@@ -273,10 +529,23 @@ pub struct GameLoopData {
     controls: Controls,
     game_config: GameConfig,
     storage: AppStorage,
+    /// Where the current game is saved
+    save_slot: SaveSlot,
+    /// The day and turn when the current game was last saved, or `None` if it hasn't been
+    /// saved since it was started or loaded
+    last_save: Option<(u32, u64)>,
     rng_seed_source: RngSeedSource,
     config: Config,
     images: Images,
     cursor: Option<Coord>,
+    examining: bool,
+    /// Where the player is walking to without further input, if anywhere
+    travel: Option<Travel>,
+    /// Time since the player last took a step of `travel`
+    travel_timer: Duration,
+    /// Commands typed into the developer console and their output
+    console_log: Vec<String>,
+    audio_state: crate::audio::AudioState,
 }
 
 impl GameLoopData {
@@ -288,15 +557,18 @@ impl GameLoopData {
     ) -> (Self, GameLoopState) {
         let mut rng_seed_source = RngSeedSource::new(initial_rng_seed);
         let config = storage.load_config().unwrap_or_default();
-        let (instance, state) = match storage.load_game() {
-            Some(instance) => {
-                let (instance, running) = instance.into_game_instance();
+        let save_slot = storage.most_recent_slot().unwrap_or_default();
+        let (instance, state) = match storage.load_game(save_slot) {
+            Ok(Some((instance, replay))) => {
+                let (instance, running) = instance.into_game_instance(replay, &game_config);
                 (
                     Some(instance),
                     GameLoopState::Playing(running.into_witness()),
                 )
             }
-            None => {
+            // Explain why the run can't be continued rather than replacing it with a new game
+            Err(e) => (None, GameLoopState::LoadFailed(e)),
+            Ok(None) => {
                 if force_new_game {
                     let (instance, running) =
                         new_game(&mut rng_seed_source, &game_config, config.victories.clone());
@@ -316,16 +588,25 @@ impl GameLoopData {
             storage.save_controls(&controls);
             controls
         };
+        let audio_state =
+            crate::audio::AudioState::new_default(config.music_volume, config.sfx_volume);
         (
             Self {
                 instance,
                 controls,
                 game_config,
                 storage,
+                save_slot,
+                last_save: None,
                 rng_seed_source,
                 config,
                 images: Images::new(),
                 cursor: None,
+                examining: false,
+                travel: None,
+                travel_timer: Duration::ZERO,
+                console_log: Vec::new(),
+                audio_state,
             },
             state,
         )
@@ -339,31 +620,179 @@ impl GameLoopData {
     }
 
     fn save_instance(&mut self, running: witness::Running) -> witness::Running {
-        let instance = self.instance.take().unwrap().into_storable(running);
-        self.storage.save_game(&instance);
-        let (instance, running) = instance.into_game_instance();
+        let instance = self.instance.take().unwrap();
+        let metadata = SaveMetadata::new(instance.game.inner_ref(), instance.game.replay());
+        self.last_save = Some((metadata.day, metadata.num_turns));
+        let (instance, replay) = instance.into_storable(running);
+        self.storage
+            .save_game(self.save_slot, &instance, &replay, &metadata);
+        let (instance, running) = instance.into_game_instance(replay, &self.game_config);
         self.instance = Some(instance);
         running
     }
 
+    /// Save the game if a new day has started or enough turns have passed since it was last
+    /// saved
+    fn autosave_if_due(&mut self, running: witness::Running) -> witness::Running {
+        let game = match self.game() {
+            Some(game) => game,
+            None => return running,
+        };
+        let now = (game.current_day(), game.victory_stats().num_turns);
+        let is_due = match self.last_save {
+            Some((day, turn)) => now.0 != day || now.1 >= turn + AUTOSAVE_INTERVAL_TURNS,
+            None => {
+                // count from when the game was started or loaded
+                self.last_save = Some(now);
+                false
+            }
+        };
+        if is_due {
+            self.save_instance(running)
+        } else {
+            running
+        }
+    }
+
     fn clear_saved_game(&mut self) {
-        self.storage.clear_game();
+        self.storage.clear_game(self.save_slot);
+    }
+
+    /// Every save slot, with a summary of the game saved there if there is one
+    fn save_slots(&self) -> Vec<(SaveSlot, Option<String>)> {
+        SaveSlot::all()
+            .map(|slot| {
+                let summary = match self.storage.load_metadata(slot) {
+                    Some(metadata) => Some(metadata.summary()),
+                    // saved before save slots existed
+                    None if self.storage.has_saved_game(slot) => Some(format!("Saved game")),
+                    None => None,
+                };
+                (slot, summary)
+            })
+            .collect()
+    }
+
+    /// Switch to the game saved in a slot. Returns `Ok(None)` if nothing is saved there.
+    fn load_slot(&mut self, slot: SaveSlot) -> Result<Option<witness::Running>, LoadGameError> {
+        let (instance, replay) = match self.storage.load_game(slot)? {
+            Some(save) => save,
+            None => return Ok(None),
+        };
+        let (instance, running) = instance.into_game_instance(replay, &self.game_config);
+        self.save_slot = slot;
+        self.instance = Some(instance);
+        self.travel = None;
+        self.last_save = None;
+        Ok(Some(running))
+    }
+
+    /// Start a new game which will be saved in a slot, replacing anything saved there
+    fn new_game_in_slot(&mut self, slot: SaveSlot) -> witness::Running {
+        self.storage.clear_game(slot);
+        self.save_slot = slot;
+        self.new_game()
+    }
+
+    /// Store the replay of the current game so it can be attached to bug reports
+    fn save_replay(&mut self) {
+        if let Some(instance) = self.instance.as_ref() {
+            self.storage
+                .save_replay(self.save_slot, instance.game.replay());
+        }
     }
 
     fn new_game(&mut self) -> witness::Running {
         let victories = self.config.victories.clone();
         let (instance, running) = new_game(&mut self.rng_seed_source, &self.game_config, victories);
         self.instance = Some(instance);
+        self.travel = None;
+        self.last_save = None;
+        running
+    }
+
+    /// Play the sounds made during the most recent action
+    fn play_sounds(&mut self) {
+        if let Some(instance) = self.instance.as_ref() {
+            self.audio_state
+                .play_game_events(instance.game.inner_ref().events());
+        }
+    }
+
+    pub fn game(&self) -> Option<&Game> {
+        self.instance
+            .as_ref()
+            .map(|instance| instance.game.inner_ref())
+    }
+
+    pub fn message_log(&self) -> Option<&boat_journey_game::MessageLog> {
+        self.game().map(|game| game.message_log())
+    }
+
+    pub fn console_log(&self) -> &[String] {
+        &self.console_log
+    }
+
+    /// Run a line typed into the developer console, recording it and its output in the
+    /// console log
+    pub fn run_console_command(
+        &mut self,
+        line: &str,
+        mut running: witness::Running,
+    ) -> witness::Running {
+        let mut output = vec![format!("> {}", line)];
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        if words == ["help"] {
+            let usages = DEBUG_COMMANDS.iter().chain([&DEBUG_OVERLAY_USAGE]);
+            output.extend(usages.map(|usage| format!("  {}", usage)));
+        } else if let Some(instance) = self.instance.as_mut() {
+            // overlays are part of the app rather than the game so they're handled here
+            let result = match words.as_slice() {
+                ["overlay", name] => instance
+                    .debug_overlays
+                    .toggle(name)
+                    .map(|shown| format!("{} overlay {}.", name, if shown { "on" } else { "off" }))
+                    .ok_or_else(|| format!("Usage: {}", DEBUG_OVERLAY_USAGE)),
+                ["overlay", ..] => Err(format!("Usage: {}", DEBUG_OVERLAY_USAGE)),
+                _ => match DebugCommand::parse(line) {
+                    Ok(command) => {
+                        let (running_, result) =
+                            running.debug(&mut instance.game, &self.game_config, command);
+                        running = running_;
+                        result.map_err(|e| e.message())
+                    }
+                    Err(e) => Err(e.message()),
+                },
+            };
+            output.push(result.unwrap_or_else(|message| message));
+        }
+        self.console_log.extend(output);
+        let excess = self.console_log.len().saturating_sub(CONSOLE_LOG_CAPACITY);
+        self.console_log.drain(..excess);
         running
     }
 
+    fn set_controls(&mut self, controls: Controls) {
+        self.storage.save_controls(&controls);
+        self.controls = controls;
+    }
+
     fn save_config(&mut self) {
         self.storage.save_config(&self.config);
     }
 
     fn render(&self, ctx: Ctx, fb: &mut FrameBuffer) {
         let instance = self.instance.as_ref().unwrap();
-        instance.render(ctx, fb, self.cursor.is_some());
+        let cursor_mode = self.cursor.map(|cursor| {
+            if self.examining {
+                CursorMode::Examine(
+                    self.screen_coord_to_game_coord(cursor, ctx.bounding_box.size()),
+                )
+            } else {
+                CursorMode::Aim
+            }
+        });
+        instance.render(ctx, fb, cursor_mode);
         if let Some(cursor) = self.cursor {
             let cursor_colour = Rgba32::new(255, 255, 255, 127);
             let render_cell = RenderCell::default().with_background(cursor_colour);
@@ -371,12 +800,169 @@ impl GameLoopData {
         }
     }
 
-    fn update(&mut self, event: Event, running: witness::Running) -> GameLoopState {
+    /// Clicking the boat controls while standing on them toggles driving. Clicking anywhere else
+    /// while on foot travels there.
+    fn click(
+        &mut self,
+        screen_coord: Coord,
+        screen_size: Size,
+        running: witness::Running,
+    ) -> GameLoopState {
+        let coord = self.screen_coord_to_game_coord(screen_coord, screen_size);
+        let instance = self.instance.as_mut().unwrap();
+        let game = instance.game.inner_ref();
+        let boat_coord = game.boat_coord();
+        if boat_coord == Some(coord) && boat_coord == Some(game.player_coord()) {
+            let (witness, action_result) =
+                running.drive_toggle(&mut instance.game, &self.game_config);
+            instance.action_error = action_result.err().map(Into::into);
+            self.play_sounds();
+            return GameLoopState::Playing(witness);
+        }
+        self.start_travel(Travel::Destination(coord));
+        GameLoopState::Playing(running.into_witness())
+    }
+
+    /// The direction of the next step of travel, or the reason it can't continue
+    fn travel_direction(
+        game: &Game,
+        travel: &mut Travel,
+    ) -> Result<CardinalDirection, TravelError> {
+        match travel {
+            Travel::Destination(coord) => game
+                .direction_towards(*coord)
+                .ok_or(TravelError::NoKnownPath),
+            Travel::Explore => game
+                .direction_to_explore()
+                .ok_or(TravelError::NothingToExplore),
+            Travel::Autopilot(autopilot) => autopilot
+                .next_direction(game)
+                .map_err(TravelError::Autopilot),
+        }
+    }
+
+    /// Start walking on foot, or driving with the autopilot, without further input unless
+    /// there's a reason not to
+    fn start_travel(&mut self, mut travel: Travel) {
+        let game = self.instance.as_ref().unwrap().game.inner_ref();
+        let is_autopilot = matches!(travel, Travel::Autopilot(_));
+        if game.is_driving() != is_autopilot {
+            return;
+        }
+        let action_error = if game.is_enemy_visible() {
+            Some(TravelError::EnemyInView)
+        } else {
+            // the autopilot checks its route when it's engaged
+            let first_step = if is_autopilot {
+                Ok(())
+            } else {
+                Self::travel_direction(game, &mut travel).map(|_| ())
+            };
+            match first_step {
+                Ok(()) => {
+                    self.travel = Some(travel);
+                    // take the first step straight away
+                    self.travel_timer = TRAVEL_STEP_INTERVAL;
+                    None
+                }
+                Err(error) => Some(error),
+            }
+        };
+        self.instance.as_mut().unwrap().action_error = action_error.map(Into::into);
+    }
+
+    /// Engage the autopilot if there's a route to the destination
+    fn start_autopilot(&mut self, destination: AutopilotDestination) {
+        let game = self.instance.as_ref().unwrap().game.inner_ref();
+        match Autopilot::new(game, destination) {
+            Ok(autopilot) => self.start_travel(Travel::Autopilot(autopilot)),
+            Err(stop) => {
+                self.instance.as_mut().unwrap().action_error =
+                    Some(TravelError::Autopilot(stop).into())
+            }
+        }
+    }
+
+    /// Take the next step of the current travel once enough time has passed since the previous
+    /// step. Travel stops on arrival, when an enemy comes into view, or if anything happens
+    /// that's worth a message.
+    fn travel_step(&mut self, running: witness::Running, since_previous: Duration) -> Witness {
+        let mut travel = match self.travel.take() {
+            Some(travel) => travel,
+            None => return running.into_witness(),
+        };
+        self.travel_timer += since_previous;
+        if self.travel_timer < TRAVEL_STEP_INTERVAL {
+            self.travel = Some(travel);
+            return running.into_witness();
+        }
+        self.travel_timer = Duration::ZERO;
+        let instance = self.instance.as_mut().unwrap();
+        let direction = match Self::travel_direction(instance.game.inner_ref(), &mut travel) {
+            Ok(direction) => direction,
+            Err(error) => {
+                // walking stops quietly on arrival, but the autopilot explains why it stopped
+                match (travel, error) {
+                    (Travel::Autopilot(_), TravelError::Autopilot(AutopilotStop::Arrived)) => {
+                        instance.notice = Some(AutopilotStop::Arrived.message());
+                    }
+                    (Travel::Autopilot(_), error) => instance.action_error = Some(error.into()),
+                    _ => (),
+                }
+                return running.into_witness();
+            }
+        };
+        let (witness, action_result) =
+            running.walk(&mut instance.game, direction, &self.game_config);
+        let game = instance.game.inner_ref();
+        let interrupted =
+            game.is_enemy_visible() || game.events().iter().any(|event| event.message().is_some());
+        if action_result.is_ok() && !interrupted && matches!(witness, Witness::Running(_)) {
+            self.travel = Some(travel);
+        }
+        instance.action_error = action_result.err().map(Into::into);
+        self.play_sounds();
+        witness
+    }
+
+    fn update(
+        &mut self,
+        event: Event,
+        running: witness::Running,
+        screen_size: Size,
+    ) -> GameLoopState {
         let instance = self.instance.as_mut().unwrap();
         let witness = match event {
             Event::Input(input) => {
+                if self.game_config.debug && input == chargrid::input::Input::Keyboard(CONSOLE_KEY)
+                {
+                    self.travel = None;
+                    return GameLoopState::Console(running);
+                }
                 if let Some(app_input) = self.controls.get(input) {
-                    let (witness, _action_result) = match app_input {
+                    self.travel = None;
+                    instance.notice = None;
+                    match app_input {
+                        AppInput::Examine => return GameLoopState::Examine(running),
+                        AppInput::WorldMap => return GameLoopState::WorldMap(running),
+                        AppInput::Click(screen_coord) => {
+                            return self.click(screen_coord, screen_size, running)
+                        }
+                        AppInput::Explore => {
+                            self.start_travel(Travel::Explore);
+                            return GameLoopState::Playing(running.into_witness());
+                        }
+                        AppInput::Autopilot => {
+                            if instance.game.inner_ref().is_driving() {
+                                return GameLoopState::Autopilot(running);
+                            }
+                            instance.action_error =
+                                Some(TravelError::Autopilot(AutopilotStop::NotDriving).into());
+                            return GameLoopState::Playing(running.into_witness());
+                        }
+                        _ => (),
+                    }
+                    let (witness, action_result) = match app_input {
                         AppInput::Direction(direction) => {
                             running.walk(&mut instance.game, direction, &self.game_config)
                         }
@@ -387,13 +973,23 @@ impl GameLoopData {
                         AppInput::Ability(i) => {
                             running.ability(&mut instance.game, &self.game_config, i)
                         }
+                        AppInput::Examine
+                        | AppInput::WorldMap
+                        | AppInput::Click(_)
+                        | AppInput::Explore
+                        | AppInput::Autopilot => unreachable!(),
                     };
+                    instance.action_error = action_result.err().map(Into::into);
+                    self.play_sounds();
                     witness
                 } else {
                     running.into_witness()
                 }
             }
             Event::Tick(since_previous) => {
+                instance
+                    .mist
+                    .set_weather(instance.game.inner_ref().weather());
                 instance.mist.tick();
                 let fade_speed = 8;
                 if instance.fade_state.player_fading {
@@ -407,11 +1003,22 @@ impl GameLoopData {
                         instance.fade_state.boat_opacity.saturating_sub(fade_speed);
                 }
 
-                running.tick(&mut instance.game, since_previous, &self.game_config)
+                match running.tick(&mut instance.game, since_previous, &self.game_config) {
+                    Witness::Running(running) => self.travel_step(running, since_previous),
+                    witness => {
+                        self.travel = None;
+                        witness
+                    }
+                }
             }
             _ => Witness::Running(running),
         };
-        GameLoopState::Playing(witness)
+        match witness {
+            Witness::Running(running) => {
+                GameLoopState::Playing(self.autosave_if_due(running).into_witness())
+            }
+            witness => GameLoopState::Playing(witness),
+        }
     }
 }
 
@@ -424,7 +1031,14 @@ impl GameInstanceComponent {
 }
 
 pub enum GameLoopState {
+    /// The saved game couldn't be loaded
+    LoadFailed(LoadGameError),
     Paused(witness::Running),
+    Examine(witness::Running),
+    WorldMap(witness::Running),
+    Autopilot(witness::Running),
+    /// The developer console is open
+    Console(witness::Running),
     Playing(Witness),
     MainMenu,
 }
@@ -437,12 +1051,12 @@ impl Component for GameInstanceComponent {
         state.render(ctx, fb);
     }
 
-    fn update(&mut self, state: &mut Self::State, _ctx: Ctx, event: Event) -> Self::Output {
+    fn update(&mut self, state: &mut Self::State, ctx: Ctx, event: Event) -> Self::Output {
         let running = witness::Running::cheat(); // XXX
         if event.is_escape() {
             GameLoopState::Paused(running)
         } else {
-            state.update(event, running)
+            state.update(event, running, ctx.bounding_box.size())
         }
     }
 
@@ -476,7 +1090,7 @@ impl Component for GameInstanceComponentAim {
         };
         match event {
             Event::Tick(_) | Event::Peek => {
-                state.update(event, running);
+                state.update(event, running, ctx.bounding_box.size());
                 None
             }
             Event::Input(input) => {
@@ -521,6 +1135,122 @@ impl Component for GameInstanceComponentAim {
     }
 }
 
+/// Move the cursor around the game without taking any actions, describing whatever is under it.
+/// Yields the coordinate to travel to if one is chosen.
+struct GameInstanceComponentExamine;
+
+impl Component for GameInstanceComponentExamine {
+    type Output = Option<Option<Coord>>;
+    type State = GameLoopData;
+
+    fn render(&self, state: &Self::State, ctx: Ctx, fb: &mut FrameBuffer) {
+        state.render(ctx, fb);
+    }
+
+    fn update(&mut self, state: &mut Self::State, ctx: Ctx, event: Event) -> Self::Output {
+        let running = witness::Running::cheat(); // XXX
+        state.examining = true;
+        let cursor = if let Some(cursor) = state.cursor.as_mut() {
+            cursor
+        } else {
+            state.cursor = Some(ctx.bounding_box.size().to_coord().unwrap() / 2);
+            state.cursor.as_mut().unwrap()
+        };
+        match event {
+            Event::Tick(_) | Event::Peek => {
+                state.update(event, running, ctx.bounding_box.size());
+            }
+            Event::Input(input) => {
+                use chargrid::input::*;
+                match input {
+                    Input::Keyboard(key) => match key {
+                        // the keys that start examining also stop it
+                        key if key == keys::ESCAPE
+                            || state.controls.input_for(key) == Some(&AppInput::Examine) =>
+                        {
+                            state.cursor = None;
+                            state.examining = false;
+                            return Some(None);
+                        }
+                        keys::RETURN => {
+                            let coord = *cursor;
+                            state.cursor = None;
+                            state.examining = false;
+                            let coord =
+                                state.screen_coord_to_game_coord(coord, ctx.bounding_box.size());
+                            return Some(Some(coord));
+                        }
+                        KeyboardInput::Left => *cursor += Coord::new(-1, 0),
+                        KeyboardInput::Right => *cursor += Coord::new(1, 0),
+                        KeyboardInput::Up => *cursor += Coord::new(0, -1),
+                        KeyboardInput::Down => *cursor += Coord::new(0, 1),
+                        _ => (),
+                    },
+                    Input::Mouse(mouse) => {
+                        if let MouseInput::MouseMove { button: _, coord } = mouse {
+                            *cursor = coord;
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn size(&self, _state: &Self::State, ctx: Ctx) -> Size {
+        ctx.bounding_box.size()
+    }
+}
+
+/// Lets the entries of a menu be chosen by clicking on them. Each entry takes up a row of the
+/// menu, in the order they were added to it.
+struct MouseMenu<T> {
+    menu: AppCF<T>,
+    entries: Vec<T>,
+}
+
+impl<T: Clone> Component for MouseMenu<T> {
+    type Output = Option<T>;
+    type State = GameLoopData;
+
+    fn render(&self, state: &Self::State, ctx: Ctx, fb: &mut FrameBuffer) {
+        self.menu.render(state, ctx, fb);
+    }
+
+    fn update(&mut self, state: &mut Self::State, ctx: Ctx, event: Event) -> Self::Output {
+        use chargrid::input::*;
+        if let Event::Input(Input::Mouse(MouseInput::MousePress {
+            button: MouseButton::Left,
+            coord,
+        })) = event
+        {
+            let size = self.menu.size(state, ctx);
+            let coord = coord - ctx.bounding_box.top_left();
+            if coord.x >= 0 && coord.y >= 0 && (coord.x as u32) < size.width() {
+                if let Some(entry) = self.entries.get(coord.y as usize) {
+                    return Some(entry.clone());
+                }
+            }
+        }
+        self.menu.update(state, ctx, event)
+    }
+
+    fn size(&self, state: &Self::State, ctx: Ctx) -> Size {
+        self.menu.size(state, ctx)
+    }
+}
+
+/// Build a menu whose entries can also be chosen with the mouse
+fn build_mouse_menu<T: Clone + 'static>(
+    builder: menu::builder::MenuBuilder<T>,
+    entries: Vec<T>,
+) -> AppCF<T> {
+    cf(MouseMenu {
+        menu: builder.build_cf(),
+        entries,
+    })
+}
+
 fn menu_style<T: 'static>(menu: AppCF<T>) -> AppCF<T> {
     menu.border(BorderStyle::default())
         .fill(MENU_BACKGROUND)
@@ -535,6 +1265,7 @@ fn menu_style<T: 'static>(menu: AppCF<T>) -> AppCF<T> {
 #[derive(Clone)]
 enum MainMenuEntry {
     NewGame,
+    LoadGame,
     Help,
     Quit,
 }
@@ -554,20 +1285,66 @@ fn main_menu() -> AppCF<MainMenuEntry> {
     use menu::builder::*;
     use MainMenuEntry::*;
     let mut builder = menu_builder().vi_keys();
-    let mut add_item = |entry, name, ch: char| {
+    let mut entries = Vec::new();
+    let mut add_item = |entry: MainMenuEntry, name, ch: char| {
         let identifier =
             MENU_FADE_SPEC.identifier(move |b| write!(b, "({}) {}", ch, name).unwrap());
-        builder.add_item_mut(item(entry, identifier).add_hotkey_char(ch));
+        builder.add_item_mut(item(entry.clone(), identifier).add_hotkey_char(ch));
+        entries.push(entry);
     };
     add_item(NewGame, "New Game", 'n');
+    add_item(LoadGame, "Load Game", 'l');
     add_item(Help, "Help", 'h');
     #[cfg(not(feature = "web"))]
     add_item(Quit, "Quit", 'q');
-    builder.build_cf()
+    build_mouse_menu(builder, entries)
+}
+
+/// Choose a save slot. Empty slots are only listed if `include_empty` is set.
+fn save_slot_menu(slots: Vec<(SaveSlot, Option<String>)>, include_empty: bool) -> AppCF<SaveSlot> {
+    use menu::builder::*;
+    let mut builder = menu_builder().vi_keys();
+    let mut entries = Vec::new();
+    for (slot, summary) in slots {
+        let summary = match summary {
+            Some(summary) => summary,
+            None if include_empty => format!("Empty"),
+            None => continue,
+        };
+        let name = slot.name();
+        let ch = slot.hotkey();
+        let identifier = MENU_FADE_SPEC
+            .identifier(move |b| write!(b, "({}) {}: {}", ch, name, summary).unwrap());
+        builder.add_item_mut(item(slot, identifier).add_hotkey_char(ch));
+        entries.push(slot);
+    }
+    build_mouse_menu(builder, entries)
+}
+
+/// Ask before starting a new game in a slot which already has a saved game. Yields whether to
+/// go ahead.
+fn confirm_overwrite(state: &State, slot: SaveSlot) -> AppCF<bool> {
+    use menu::builder::*;
+    if !state.storage.has_saved_game(slot) {
+        return val_once(true);
+    }
+    let mut builder = menu_builder().vi_keys();
+    let mut entries = Vec::new();
+    let mut add_item = |confirmed, name: String, ch: char| {
+        let identifier =
+            MENU_FADE_SPEC.identifier(move |b| write!(b, "({}) {}", ch, name).unwrap());
+        builder.add_item_mut(item(confirmed, identifier).add_hotkey_char(ch));
+        entries.push(confirmed);
+    };
+    add_item(false, format!("Cancel"), 'n');
+    add_item(true, format!("Overwrite the game in {}", slot.name()), 'y');
+    main_menu_decorate(build_mouse_menu(builder, entries))
+        .map(|confirmed| matches!(confirmed, Ok(true)))
 }
 
 enum MainMenuOutput {
     NewGame { new_running: witness::Running },
+    LoadGame { running: witness::Running },
     Quit,
 }
 
@@ -587,6 +1364,21 @@ fn background() -> CF<(), State> {
     .ignore_state()
 }
 
+fn load_failed(e: LoadGameError) -> AppCF<()> {
+    text::load_failed(MAIN_MENU_TEXT_WIDTH, e.message())
+        .centre()
+        .overlay(background(), 1)
+}
+
+/// Style a menu shown from the main menu
+fn main_menu_decorate<T: 'static>(menu: AppCF<T>) -> AppCF<Result<T, Close>> {
+    menu.menu_harness()
+        .border(BorderStyle::default())
+        .fill(MENU_BACKGROUND)
+        .centre()
+        .overlay(background(), 1)
+}
+
 fn main_menu_loop() -> AppCF<MainMenuOutput> {
     use MainMenuEntry::*;
     title_decorate(main_menu())
@@ -597,15 +1389,44 @@ fn main_menu_loop() -> AppCF<MainMenuOutput> {
             1,
         )
         .repeat_unit(move |entry| match entry {
-            NewGame => text::loading(MAIN_MENU_TEXT_WIDTH)
-                .centre()
-                .overlay(background(), 1)
-                .then(|| {
-                    on_state(|state: &mut State| MainMenuOutput::NewGame {
-                        new_running: state.new_game(),
-                    })
-                })
-                .break_(),
+            NewGame => on_state_then(|state: &mut State| {
+                main_menu_decorate(save_slot_menu(state.save_slots(), true))
+            })
+            .and_then(|slot_or_escape| match slot_or_escape {
+                Ok(slot) => on_state_then(move |state: &mut State| confirm_overwrite(state, slot))
+                    .and_then(move |confirmed| {
+                        if confirmed {
+                            text::loading(MAIN_MENU_TEXT_WIDTH)
+                                .centre()
+                                .overlay(background(), 1)
+                                .then(move || {
+                                    on_state(move |state: &mut State| MainMenuOutput::NewGame {
+                                        new_running: state.new_game_in_slot(slot),
+                                    })
+                                })
+                                .break_()
+                        } else {
+                            continue_(())
+                        }
+                    }),
+                Err(_) => continue_(()),
+            }),
+            LoadGame => on_state_then(|state: &mut State| {
+                let slots = state.save_slots();
+                if slots.iter().all(|(_, summary)| summary.is_none()) {
+                    // there's nothing to load
+                    return val_once(Err(Close));
+                }
+                main_menu_decorate(save_slot_menu(slots, false))
+            })
+            .and_then(|slot_or_escape| match slot_or_escape {
+                Ok(slot) => on_state_then(move |state: &mut State| match state.load_slot(slot) {
+                    Ok(Some(running)) => val_once(MainMenuOutput::LoadGame { running }).break_(),
+                    Ok(None) => continue_(()),
+                    Err(e) => load_failed(e).continue_(),
+                }),
+                Err(_) => continue_(()),
+            }),
             Help => text::help(MAIN_MENU_TEXT_WIDTH)
                 .fill(crate::colour::MURKY_GREEN.to_rgba32(255))
                 .centre()
@@ -621,6 +1442,8 @@ enum PauseMenuEntry {
     SaveQuit,
     Save,
     NewGame,
+    MessageLog,
+    Keybindings,
     Help,
     Clear,
 }
@@ -629,10 +1452,12 @@ fn pause_menu() -> AppCF<PauseMenuEntry> {
     use menu::builder::*;
     use PauseMenuEntry::*;
     let mut builder = menu_builder().vi_keys();
-    let mut add_item = |entry, name, ch: char| {
+    let mut entries = Vec::new();
+    let mut add_item = |entry: PauseMenuEntry, name, ch: char| {
         let identifier =
             MENU_FADE_SPEC.identifier(move |b| write!(b, "({}) {}", ch, name).unwrap());
-        builder.add_item_mut(item(entry, identifier).add_hotkey_char(ch));
+        builder.add_item_mut(item(entry.clone(), identifier).add_hotkey_char(ch));
+        entries.push(entry);
     };
     add_item(Resume, "Resume", 'r');
     #[cfg(not(feature = "web"))]
@@ -640,9 +1465,11 @@ fn pause_menu() -> AppCF<PauseMenuEntry> {
     #[cfg(not(feature = "web"))]
     add_item(Save, "Save", 's');
     add_item(NewGame, "New Game", 'n');
+    add_item(MessageLog, "Message Log", 'l');
+    add_item(Keybindings, "Controls", 'o');
     add_item(Help, "Help", 'h');
     add_item(Clear, "Clear", 'c');
-    builder.build_cf()
+    build_mouse_menu(builder, entries)
 }
 
 fn pause_menu_loop(running: witness::Running) -> AppCF<PauseOutput> {
@@ -677,6 +1504,8 @@ fn pause_menu_loop(running: witness::Running) -> AppCF<PauseOutput> {
                             })
                         })
                         .break_(),
+                    MessageLog => message_log::message_log().continue_with(running),
+                    Keybindings => controls_menu_loop().continue_with(running),
                     Help => text::help(text_width).continue_with(running),
                     Clear => on_state(|state: &mut State| {
                         state.clear_saved_game();
@@ -689,12 +1518,159 @@ fn pause_menu_loop(running: witness::Running) -> AppCF<PauseOutput> {
         )
 }
 
+#[derive(Clone)]
+enum ControlsMenuEntry {
+    Rebind(AppInput),
+    ViKeys,
+    Wasd,
+    Reset,
+    Save,
+}
+
+fn controls_menu(controls: &Controls) -> AppCF<ControlsMenuEntry> {
+    use menu::builder::*;
+    use ControlsMenuEntry::*;
+    let mut builder = menu_builder().vi_keys();
+    let mut entries = Vec::new();
+    for app_input in AppInput::all() {
+        let name = app_input.name();
+        let keys = controls
+            .keys_for(&app_input)
+            .into_iter()
+            .map(controls::key_name)
+            .collect::<Vec<_>>()
+            .join(", ");
+        let identifier =
+            MENU_FADE_SPEC.identifier(move |b| write!(b, "{:24}{}", name, keys).unwrap());
+        builder.add_item_mut(item(Rebind(app_input.clone()), identifier));
+        entries.push(Rebind(app_input));
+    }
+    let mut add_item = |entry: ControlsMenuEntry, name, ch: char| {
+        let identifier =
+            MENU_FADE_SPEC.identifier(move |b| write!(b, "({}) {}", ch, name).unwrap());
+        builder.add_item_mut(item(entry.clone(), identifier).add_hotkey_char(ch));
+        entries.push(entry);
+    };
+    add_item(ViKeys, "Vi-keys Preset", 'v');
+    add_item(Wasd, "WASD Preset", 'w');
+    add_item(Reset, "Reset to Defaults", 'r');
+    add_item(Save, "Save", 's');
+    build_mouse_menu(builder, entries)
+}
+
+/// Edit a copy of the controls, which only replaces the current controls if it's saved
+fn controls_menu_loop() -> AppCF<()> {
+    use ControlsMenuEntry::*;
+    on_state_then(|state: &mut State| {
+        loop_(state.controls.clone(), |controls| {
+            controls_menu(&controls)
+                .menu_harness()
+                .and_then(move |entry_or_escape| match entry_or_escape {
+                    Ok(entry) => match entry {
+                        Rebind(app_input) => cf(KeyCaptureComponent {
+                            app_input,
+                            controls,
+                            conflict: None,
+                        })
+                        .continue_(),
+                        ViKeys => val_once(Controls::vi_keys()).continue_(),
+                        Wasd => val_once(Controls::wasd()).continue_(),
+                        Reset => val_once(Controls::default()).continue_(),
+                        Save => {
+                            on_state(move |state: &mut State| state.set_controls(controls)).break_()
+                        }
+                    },
+                    Err(_escape_or_start) => break_(()),
+                })
+        })
+    })
+}
+
+/// Waits for a key to bind to an input, refusing keys already used for something else
+struct KeyCaptureComponent {
+    app_input: AppInput,
+    controls: Controls,
+    conflict: Option<(chargrid::input::KeyboardInput, AppInput)>,
+}
+
+impl Component for KeyCaptureComponent {
+    type Output = Option<Controls>;
+    type State = GameLoopData;
+
+    fn render(&self, _state: &Self::State, ctx: Ctx, fb: &mut FrameBuffer) {
+        use chargrid::text::*;
+        let mut text = vec![StyledString {
+            string: format!(
+                "Press a key for {}.\n\nPress escape to cancel.\n\n",
+                self.app_input.name()
+            ),
+            style: Style::plain_text(),
+        }];
+        if let Some((key, other)) = self.conflict.as_ref() {
+            text.push(StyledString {
+                string: format!(
+                    "{} is already used for {}.",
+                    controls::key_name(*key),
+                    other.name()
+                ),
+                style: Style::plain_text().with_bold(true),
+            });
+        }
+        Text::new(text).render(&(), ctx, fb);
+    }
+
+    fn update(&mut self, _state: &mut Self::State, _ctx: Ctx, event: Event) -> Self::Output {
+        use chargrid::input::*;
+        if let Event::Input(Input::Keyboard(key)) = event {
+            if key == keys::ESCAPE {
+                return Some(self.controls.clone());
+            }
+            match self.controls.input_for(key) {
+                Some(other) if *other != self.app_input => {
+                    self.conflict = Some((key, other.clone()));
+                }
+                _ => {
+                    let mut controls = self.controls.clone();
+                    controls.rebind(self.app_input.clone(), key);
+                    return Some(controls);
+                }
+            }
+        }
+        None
+    }
+
+    fn size(&self, _state: &Self::State, _ctx: Ctx) -> Size {
+        Size::new(48, 8)
+    }
+}
+
 enum PauseOutput {
     ContinueGame { running: witness::Running },
     MainMenu,
     Quit,
 }
 
+/// Choose where the autopilot should take the boat, or `None` if the menu is closed
+fn autopilot_menu() -> AppCF<Option<AutopilotDestination>> {
+    use menu::builder::*;
+    let mut builder = menu_builder().vi_keys();
+    let mut entries = Vec::new();
+    let mut add_item = |destination: AutopilotDestination, ch: char| {
+        let name = destination.name();
+        let identifier =
+            MENU_FADE_SPEC.identifier(move |b| write!(b, "({}) Go to {}", ch, name).unwrap());
+        builder.add_item_mut(item(destination, identifier).add_hotkey_char(ch));
+        entries.push(destination);
+    };
+    add_item(AutopilotDestination::NextTown, 't');
+    add_item(AutopilotDestination::Ocean, 'o');
+    menu_style(
+        build_mouse_menu(builder, entries)
+            .menu_harness()
+            .map(|entry_or_escape| entry_or_escape.ok()),
+    )
+}
+
 fn pause(running: witness::Running) -> AppCF<PauseOutput> {
     menu_style(pause_menu_loop(running))
 }
@@ -707,6 +1683,10 @@ fn game_instance_component_aim() -> AppCF<AimResult> {
     cf(GameInstanceComponentAim)
 }
 
+fn game_instance_component_examine() -> AppCF<Option<Coord>> {
+    cf(GameInstanceComponentExamine)
+}
+
 fn win(win_: witness::Win) -> AppCF<()> {
     use chargrid::{
         text::{StyledString, Text},
@@ -730,6 +1710,7 @@ fn win(win_: witness::Win) -> AppCF<()> {
     .overlay(game_instance_component(win_.into_running()), 1)
     .then(|| {
         on_state_then(move |state: &mut State| {
+            state.save_replay();
             state.clear_saved_game();
             state.config.won = true;
             state.save_config();
@@ -770,6 +1751,7 @@ fn win(win_: witness::Win) -> AppCF<()> {
 
 fn game_over(reason: GameOverReason) -> AppCF<()> {
     on_state_then(move |state: &mut State| {
+        state.save_replay();
         state.clear_saved_game();
         state.save_config();
         text::game_over(MAIN_MENU_TEXT_WIDTH, reason)
@@ -782,9 +1764,11 @@ fn game_menu(menu_witness: witness::Menu) -> AppCF<Witness> {
     use chargrid::align::*;
     use menu::builder::*;
     let mut builder = menu_builder();
-    let mut add_item = |entry, name, ch: char| {
+    let mut entries = Vec::new();
+    let mut add_item = |entry: GameMenuChoice, name, ch: char| {
         let identifier = MENU_FADE_SPEC.identifier(move |b| write!(b, "{}. {}", ch, name).unwrap());
-        builder.add_item_mut(item(entry, identifier).add_hotkey_char(ch));
+        builder.add_item_mut(item(entry.clone(), identifier).add_hotkey_char(ch));
+        entries.push(entry);
     };
     for (i, choice) in menu_witness.menu.choices.iter().enumerate() {
         let ch = std::char::from_digit(i as u32 + 1, 10).unwrap();
@@ -804,6 +1788,17 @@ fn game_menu(menu_witness: witness::Menu) -> AppCF<Witness> {
                 format!("Buy passenger space ({cost} junk)"),
                 ch,
             ),
+            GameMenuChoice::RepairHull(cost) => {
+                add_item(choice.clone(), format!("Repair hull ({cost} junk)"), ch)
+            }
+            GameMenuChoice::BoatUpgrades => {
+                add_item(choice.clone(), "Browse boat upgrades".to_string(), ch)
+            }
+            GameMenuChoice::BuyUpgrade { upgrade, cost } => add_item(
+                choice.clone(),
+                format!("{} ({cost} junk)", upgrade.name()),
+                ch,
+            ),
             GameMenuChoice::BuyFuel { amount, cost } => add_item(
                 choice.clone(),
                 format!("Buy {amount} fuel ({cost} junk)"),
@@ -842,8 +1837,7 @@ fn game_menu(menu_witness: witness::Menu) -> AppCF<Witness> {
         .cf::<State>()
         .set_width(36)
     };
-    let menu_cf = builder
-        .build_cf()
+    let menu_cf = build_mouse_menu(builder, entries)
         .menu_harness()
         .add_x(2)
         .with_title_vertical(title, 2)
@@ -862,21 +1856,19 @@ fn game_menu(menu_witness: witness::Menu) -> AppCF<Witness> {
             1,
         );
     menu_cf.and_then_side_effect(|result, state: &mut State| {
+        let instance = state.instance.as_mut().unwrap();
         let witness = match result {
-            Err(Close) => menu_witness.cancel(),
+            Err(Close) => menu_witness.cancel(&mut instance.game),
             Ok(choice) => {
-                if let Some(instance) = state.instance.as_mut() {
-                    let witness = menu_witness.commit(&mut instance.game, choice.clone());
-                    if let GameMenuChoice::SleepUntilMorning(i) = choice {
-                        return text::sleep(MAIN_MENU_TEXT_WIDTH, i)
-                            .centre()
-                            .overlay(background(), 1)
-                            .map_val(|| witness);
-                    }
-                    witness
-                } else {
-                    menu_witness.cancel()
+                let witness = menu_witness.commit(&mut instance.game, choice.clone());
+                state.play_sounds();
+                if let GameMenuChoice::SleepUntilMorning(i) = choice {
+                    return text::sleep(MAIN_MENU_TEXT_WIDTH, i)
+                        .centre()
+                        .overlay(background(), 1)
+                        .map_val(|| witness);
                 }
+                witness
             }
         };
         val_once(witness)
@@ -884,13 +1876,15 @@ fn game_menu(menu_witness: witness::Menu) -> AppCF<Witness> {
 }
 
 fn aim(aim_: witness::Aim) -> AppCF<Witness> {
-    game_instance_component_aim().map_side_effect(|result, state: &mut State| match result {
-        AimResult::Cancel => aim_.cancel(),
-        AimResult::Coord(coord) => {
-            if let Some(instance) = state.instance.as_mut() {
-                aim_.commit(&mut instance.game, coord)
-            } else {
-                aim_.cancel()
+    game_instance_component_aim().map_side_effect(|result, state: &mut State| {
+        let instance = state.instance.as_mut().unwrap();
+        match result {
+            AimResult::Cancel => aim_.cancel(&mut instance.game),
+            AimResult::Coord(coord) => {
+                let (witness, action_result) = aim_.commit(&mut instance.game, coord);
+                instance.action_error = action_result.err().map(Into::into);
+                state.play_sounds();
+                witness
             }
         }
     })
@@ -906,6 +1900,33 @@ pub fn game_loop_component(initial_state: GameLoopState) -> AppCF<()> {
             Witness::Menu(menu_) => game_menu(menu_).map(Playing).continue_(),
             Witness::Aim(aim_) => aim(aim_).map(Playing).continue_(),
         },
+        Examine(running) => game_instance_component_examine()
+            .map_side_effect(|destination, state: &mut State| {
+                if let Some(coord) = destination {
+                    let is_driving = state.game().map(|game| game.is_driving());
+                    if is_driving == Some(true) {
+                        state.start_autopilot(AutopilotDestination::Point(coord));
+                    } else {
+                        state.start_travel(Travel::Destination(coord));
+                    }
+                }
+                Playing(running.into_witness())
+            })
+            .continue_(),
+        WorldMap(running) => world_map::world_map()
+            .map_val(|| Playing(running.into_witness()))
+            .continue_(),
+        Autopilot(running) => autopilot_menu()
+            .map_side_effect(|destination, state: &mut State| {
+                if let Some(destination) = destination {
+                    state.start_autopilot(destination);
+                }
+                Playing(running.into_witness())
+            })
+            .continue_(),
+        Console(running) => menu_style(console::console(running))
+            .map(|running| Playing(running.into_witness()))
+            .continue_(),
         Paused(running) => pause(running).map(|pause_output| match pause_output {
             PauseOutput::ContinueGame { running } => {
                 LoopControl::Continue(Playing(running.into_witness()))
@@ -913,12 +1934,121 @@ pub fn game_loop_component(initial_state: GameLoopState) -> AppCF<()> {
             PauseOutput::MainMenu => LoopControl::Continue(MainMenu),
             PauseOutput::Quit => LoopControl::Break(()),
         }),
+        LoadFailed(e) => load_failed(e).map_val(|| MainMenu).continue_(),
         MainMenu => main_menu_loop().map(|main_menu_output| match main_menu_output {
             MainMenuOutput::NewGame { new_running } => {
                 LoopControl::Continue(Playing(new_running.into_witness()))
             }
+            MainMenuOutput::LoadGame { running } => {
+                LoopControl::Continue(Playing(running.into_witness()))
+            }
             MainMenuOutput::Quit => LoopControl::Break(()),
         }),
     })
     .bound_size(Size::new_u16(80, 60))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::BTreeMap;
+    use storage::{LoadRawError, RemoveError, StoreRawError};
+
+    #[derive(Default)]
+    struct MemoryStorage(BTreeMap<String, Vec<u8>>);
+
+    impl Storage for MemoryStorage {
+        fn exists<K: AsRef<str>>(&self, key: K) -> bool {
+            self.0.contains_key(key.as_ref())
+        }
+
+        fn clear(&mut self) {
+            self.0.clear();
+        }
+
+        fn remove<K: AsRef<str>>(&mut self, key: K) -> Result<(), RemoveError> {
+            self.0
+                .remove(key.as_ref())
+                .map(|_| ())
+                .ok_or(RemoveError::NoSuchKey)
+        }
+
+        fn load_raw<K: AsRef<str>>(&self, key: K) -> Result<Vec<u8>, LoadRawError> {
+            self.0
+                .get(key.as_ref())
+                .cloned()
+                .ok_or(LoadRawError::NoSuchKey)
+        }
+
+        fn store_raw<K: AsRef<str>, V: AsRef<[u8]>>(
+            &mut self,
+            key: K,
+            value: V,
+        ) -> Result<(), StoreRawError> {
+            self.0
+                .insert(key.as_ref().to_string(), value.as_ref().to_vec());
+            Ok(())
+        }
+    }
+
+    fn app_storage() -> AppStorage {
+        AppStorage {
+            handle: StorageHandle::new(MemoryStorage::default()),
+            save_game_key: format!("save"),
+            config_key: format!("config"),
+            controls_key: format!("controls"),
+            replay_key: format!("replay"),
+        }
+    }
+
+    fn save(storage: &mut AppStorage, slot: SaveSlot, save: &(GameInstanceStorable, Replay)) {
+        let (instance, replay) = save;
+        storage.save_game(slot, instance, replay, &instance.metadata(replay));
+    }
+
+    fn seed((instance, replay): &(GameInstanceStorable, Replay)) -> u64 {
+        instance.metadata(replay).seed
+    }
+
+    fn loaded_seed(storage: &AppStorage, slot: SaveSlot) -> u64 {
+        seed(&storage.load_game(slot).unwrap().unwrap())
+    }
+
+    #[test]
+    fn damaged_save_falls_back_to_backup() {
+        let mut storage = app_storage();
+        let slot = SaveSlot::default();
+        let (first, second) = (
+            GameInstanceStorable::new_for_test(1),
+            GameInstanceStorable::new_for_test(2),
+        );
+        save(&mut storage, slot, &first);
+        save(&mut storage, slot, &second);
+        assert_eq!(loaded_seed(&storage, slot), seed(&second));
+        storage
+            .handle
+            .store_raw(slot.save_game_key(&storage.save_game_key), b"damaged")
+            .unwrap();
+        assert_eq!(loaded_seed(&storage, slot), seed(&first));
+    }
+
+    #[test]
+    fn damaged_save_does_not_replace_backup() {
+        let mut storage = app_storage();
+        let slot = SaveSlot::default();
+        let first = GameInstanceStorable::new_for_test(1);
+        save(&mut storage, slot, &first);
+        save(&mut storage, slot, &GameInstanceStorable::new_for_test(2));
+        let save_game_key = slot.save_game_key(&storage.save_game_key);
+        storage
+            .handle
+            .store_raw(&save_game_key, b"damaged")
+            .unwrap();
+        save(&mut storage, slot, &GameInstanceStorable::new_for_test(3));
+        storage
+            .handle
+            .store_raw(&save_game_key, b"damaged")
+            .unwrap();
+        assert_eq!(loaded_seed(&storage, slot), seed(&first));
+    }
+}
@@ -1,15 +1,29 @@
 use boat_journey_game::Config;
 use chargrid::{control_flow::*, core::*};
 
+mod audio;
 mod colour;
+mod console;
 mod controls;
 mod game_instance;
 mod game_loop;
 mod image;
+mod message_log;
 mod mist;
+mod save_format;
+mod save_slot;
+mod storage_handle;
 mod text;
+mod world_map;
 
+#[cfg(feature = "audio")]
+pub use audio::RodioAudioBackend;
+pub use audio::{AudioBackend, AudioState, NullAudioBackend};
+pub use game_instance::GameInstanceStorable;
 pub use game_loop::{AppStorage, InitialRngSeed};
+pub use save_format::{LoadGameError, SAVE_FORMAT_VERSION};
+pub use save_slot::{SaveMetadata, SaveSlot, NUM_SAVE_SLOTS};
+pub use storage_handle::StorageHandle;
 
 struct AppState {
     game_loop_data: game_loop::GameLoopData,
@@ -19,6 +33,8 @@ pub struct AppArgs {
     pub storage: AppStorage,
     pub initial_rng_seed: InitialRngSeed,
     pub omniscient: bool,
+    /// Enable the developer console
+    pub debug: bool,
     pub new_game: bool,
 }
 
@@ -27,13 +43,15 @@ pub fn app(
         storage,
         initial_rng_seed,
         omniscient,
+        debug,
         new_game,
     }: AppArgs,
 ) -> impl Component<Output = app::Output, State = ()> {
     let config = Config {
         omniscient: if omniscient { Config::OMNISCIENT } else { None },
         demo: false,
-        debug: false,
+        debug,
+        stats: Default::default(),
     };
     let (game_loop_data, initial_state) =
         game_loop::GameLoopData::new(config, storage, initial_rng_seed, new_game);
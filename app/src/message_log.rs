@@ -0,0 +1,165 @@
+use crate::game_loop::{AppCF, State};
+use chargrid::{control_flow::*, prelude::*, text::StyledString};
+
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 30;
+
+/// Scrollable list of every message from the current game, most recent at the bottom
+struct MessageLogComponent {
+    /// Number of lines hidden below the bottom of the view
+    scroll: usize,
+}
+
+/// Lines after the first line of an entry are indented by this much
+const CONTINUATION_INDENT: &str = "  ";
+
+/// Split a string into lines no longer than `width`, breaking between words where possible
+fn wrap(string: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut line_len = 0;
+    for word in string.split_whitespace() {
+        if line_len > 0 && line_len + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+            line_len = 0;
+        }
+        if line_len > 0 {
+            line.push(' ');
+            line_len += 1;
+        } else if !lines.is_empty() {
+            line.push_str(CONTINUATION_INDENT);
+            line_len = CONTINUATION_INDENT.len();
+        }
+        // words too long to fit on a line of their own are broken wherever the line ends
+        for ch in word.chars() {
+            if line_len >= width {
+                lines.push(std::mem::replace(
+                    &mut line,
+                    CONTINUATION_INDENT.to_string(),
+                ));
+                line_len = CONTINUATION_INDENT.len();
+            }
+            line.push(ch);
+            line_len += 1;
+        }
+    }
+    if line_len > 0 {
+        lines.push(line);
+    }
+    lines
+}
+
+impl MessageLogComponent {
+    /// Every entry in the log wrapped to fit the width of the view, oldest first
+    fn lines(state: &State) -> Vec<String> {
+        state
+            .message_log()
+            .map(|log| {
+                log.iter()
+                    .flat_map(|entry| {
+                        let string =
+                            format!("Day {}, turn {}: {}", entry.day, entry.turn, entry.message);
+                        wrap(&string, WIDTH as usize)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn max_scroll(state: &State) -> usize {
+        Self::lines(state).len().saturating_sub(HEIGHT as usize - 2)
+    }
+
+    fn scroll_up(&mut self, state: &State, lines: usize) {
+        self.scroll = (self.scroll + lines).min(Self::max_scroll(state));
+    }
+
+    fn scroll_down(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+}
+
+impl Component for MessageLogComponent {
+    type Output = Option<()>;
+    type State = State;
+
+    fn render(&self, state: &Self::State, ctx: Ctx, fb: &mut FrameBuffer) {
+        let num_lines = HEIGHT as usize - 2;
+        let lines = Self::lines(state);
+        for (i, line) in lines
+            .into_iter()
+            .rev()
+            .skip(self.scroll)
+            .take(num_lines)
+            .enumerate()
+        {
+            let styled_string = StyledString {
+                string: line,
+                style: Style::plain_text(),
+            };
+            styled_string.render(&(), ctx.add_y(num_lines as i32 - i as i32 - 1), fb);
+        }
+        let footer = StyledString {
+            string: format!("Up/Down: scroll, Escape: close"),
+            style: Style::plain_text().with_foreground(Rgba32::new_grey(127)),
+        };
+        footer.render(&(), ctx.add_y(HEIGHT as i32 - 1), fb);
+    }
+
+    fn update(&mut self, state: &mut Self::State, _ctx: Ctx, event: Event) -> Self::Output {
+        use chargrid::input::*;
+        let page = HEIGHT as usize - 2;
+        match event {
+            Event::Input(Input::Keyboard(key)) => match key {
+                keys::ESCAPE | KeyboardInput::Char('q') => return Some(()),
+                KeyboardInput::Up | KeyboardInput::Char('k') => self.scroll_up(state, 1),
+                KeyboardInput::Down | KeyboardInput::Char('j') => self.scroll_down(1),
+                KeyboardInput::PageUp => self.scroll_up(state, page),
+                KeyboardInput::PageDown => self.scroll_down(page),
+                KeyboardInput::Home => self.scroll_up(state, Self::max_scroll(state)),
+                KeyboardInput::End => self.scroll = 0,
+                _ => (),
+            },
+            Event::Input(Input::Mouse(MouseInput::MouseScroll { direction, .. })) => {
+                match direction {
+                    ScrollDirection::Up => self.scroll_up(state, 1),
+                    ScrollDirection::Down => self.scroll_down(1),
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+        None
+    }
+
+    fn size(&self, _state: &Self::State, _ctx: Ctx) -> Size {
+        Size::new(WIDTH, HEIGHT)
+    }
+}
+
+pub fn message_log() -> AppCF<()> {
+    cf(MessageLogComponent { scroll: 0 })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn wrap_breaks_between_words() {
+        assert_eq!(
+            wrap("the boat drifts downstream", 12),
+            vec!["the boat", "  drifts", "  downstream"]
+        );
+    }
+
+    #[test]
+    fn wrap_breaks_long_words() {
+        assert_eq!(wrap("abcdefgh", 5), vec!["abcde", "  fgh"]);
+    }
+
+    #[test]
+    fn wrap_keeps_short_strings() {
+        assert_eq!(wrap("ahoy", 64), vec!["ahoy"]);
+    }
+}
@@ -1,3 +1,4 @@
+use boat_journey_game::Weather;
 use coord_2d::Coord;
 use perlin2::Perlin2;
 use rand::Rng;
@@ -10,6 +11,8 @@ pub struct Mist {
     intensity: f64,
     offset_x: f64,
     speed_x: f64,
+    target_intensity: f64,
+    target_speed_x: f64,
 }
 
 impl Mist {
@@ -19,9 +22,23 @@ impl Mist {
             intensity: 0.03,
             offset_x: 0.,
             speed_x: 0.005,
+            target_intensity: 0.03,
+            target_speed_x: 0.005,
         }
     }
 
+    /// The mist gradually changes to match the weather
+    pub fn set_weather(&mut self, weather: Weather) {
+        let (intensity, speed_x) = match weather {
+            Weather::Clear => (0.03, 0.005),
+            Weather::Rain => (0.06, 0.01),
+            Weather::Fog => (0.2, 0.002),
+            Weather::Storm => (0.1, 0.03),
+        };
+        self.target_intensity = intensity;
+        self.target_speed_x = speed_x;
+    }
+
     pub fn get(&self, coord: Coord) -> Rgba32 {
         let noise = self
             .perlin
@@ -31,6 +48,9 @@ impl Mist {
     }
 
     pub fn tick(&mut self) {
+        let rate = 0.01;
+        self.intensity += (self.target_intensity - self.intensity) * rate;
+        self.speed_x += (self.target_speed_x - self.speed_x) * rate;
         self.offset_x += self.speed_x;
     }
 }
@@ -0,0 +1,253 @@
+use crate::{
+    game_instance::{FadeState, GameInstanceStorable},
+    mist::Mist,
+};
+use boat_journey_game::{replay::Replay, Game};
+use serde::{Deserialize, Serialize};
+
+/// The version of the save format written by this build. Increment this whenever a change to
+/// the game or app changes how a `GameInstanceStorable` is serialized, and add a migration from
+/// the previous version to `MIGRATIONS`.
+pub const SAVE_FORMAT_VERSION: u32 = 2;
+
+/// Saves older than this can't be loaded. Version 0 is the bare bincode written before saves had
+/// an envelope, and the game has changed too much since then (boat hulls, upgrades, effects,
+/// abilities, replays) to recover a version 0 save.
+const OLDEST_SUPPORTED_VERSION: u32 = 1;
+
+/// Distinguishes saves with an envelope from saves written before there was an envelope
+const MAGIC: [u8; 4] = *b"BJSV";
+
+/// Converts the serialized game from one version of the save format to the next
+type Migration = fn(Vec<u8>) -> Result<Vec<u8>, bincode::Error>;
+
+/// `MIGRATIONS[i]` converts a save from version `OLDEST_SUPPORTED_VERSION + i` to the version
+/// after it
+const MIGRATIONS: &[Migration] = &[migrate_v1_to_v2];
+
+// every supported version must be migrated to the current version
+const _: () = assert!(OLDEST_SUPPORTED_VERSION + MIGRATIONS.len() as u32 == SAVE_FORMAT_VERSION);
+
+/// Version 1 stored the replay inside the save as well as beside it. Version 2 only stores it
+/// beside the save, so the copy inside the save is dropped.
+fn migrate_v1_to_v2(data: Vec<u8>) -> Result<Vec<u8>, bincode::Error> {
+    let (game, _replay, mist, fade_state): (Game, Replay, Mist, FadeState) =
+        bincode::deserialize(&data)?;
+    bincode::serialize(&(game, mist, fade_state))
+}
+
+#[derive(Debug)]
+pub enum LoadGameError {
+    /// The save was written by a newer build of the game than this one
+    NewerVersion(u32),
+    /// The save was written by a build of the game too old for it to be migrated
+    OlderVersion(u32),
+    /// The save couldn't be read even after migrating it to the current version
+    Unreadable { version: u32, error: String },
+    /// The replay stored beside the save is missing or damaged
+    MissingReplay,
+}
+
+impl LoadGameError {
+    pub fn message(&self) -> String {
+        match self {
+            Self::NewerVersion(version) => format!(
+                "This save was made by a newer version of the game (save format {version}, \
+                this version understands up to {SAVE_FORMAT_VERSION}). Update the game to \
+                continue this run."
+            ),
+            Self::OlderVersion(version) => format!(
+                "This save (save format {version}) was made by a version of the game too old \
+                for this version to load."
+            ),
+            Self::Unreadable { version, .. } => format!(
+                "This save (save format {version}) is incompatible with this version of the \
+                game and can't be loaded."
+            ),
+            Self::MissingReplay => format!(
+                "The replay stored with this save is missing or damaged, so the save can't be \
+                loaded."
+            ),
+        }
+    }
+}
+
+/// A serialized game labelled with the version of the save format used to serialize it
+#[derive(Serialize, Deserialize)]
+pub struct SaveEnvelope {
+    magic: [u8; 4],
+    version: u32,
+    data: Vec<u8>,
+}
+
+impl SaveEnvelope {
+    pub fn new(instance: &GameInstanceStorable) -> Result<Self, bincode::Error> {
+        Ok(Self {
+            magic: MAGIC,
+            version: SAVE_FORMAT_VERSION,
+            data: bincode::serialize(instance)?,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, bincode::Error> {
+        bincode::serialize(self)
+    }
+
+    /// Saves without an envelope are treated as version 0
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        match bincode::deserialize::<Self>(&bytes) {
+            Ok(envelope) if envelope.magic == MAGIC => envelope,
+            _ => Self {
+                magic: MAGIC,
+                version: 0,
+                data: bytes,
+            },
+        }
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Whether `bytes` is a complete envelope whose version this build can load. This only reads
+    /// the envelope, not the game inside it, so it's cheap enough to check before every save.
+    pub fn is_loadable(bytes: &[u8]) -> bool {
+        match bincode::deserialize::<Self>(bytes) {
+            Ok(envelope) => {
+                envelope.magic == MAGIC
+                    && envelope.version >= OLDEST_SUPPORTED_VERSION
+                    && envelope.version <= SAVE_FORMAT_VERSION
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Migrate the save to the current version of the format and deserialize it
+    pub fn into_instance(self) -> Result<GameInstanceStorable, LoadGameError> {
+        self.into_instance_with_migrations(MIGRATIONS)
+    }
+
+    /// Like `into_instance`, but with `migrations` in place of `MIGRATIONS`. The current version
+    /// is the one the last of `migrations` converts to.
+    fn into_instance_with_migrations(
+        self,
+        migrations: &[Migration],
+    ) -> Result<GameInstanceStorable, LoadGameError> {
+        let Self { version, data, .. } = self;
+        let current_version = OLDEST_SUPPORTED_VERSION + migrations.len() as u32;
+        if version > current_version {
+            return Err(LoadGameError::NewerVersion(version));
+        }
+        if version < OLDEST_SUPPORTED_VERSION {
+            return Err(LoadGameError::OlderVersion(version));
+        }
+        let unreadable = |error: bincode::Error| LoadGameError::Unreadable {
+            version,
+            error: error.to_string(),
+        };
+        let data = migrations[(version - OLDEST_SUPPORTED_VERSION) as usize..]
+            .iter()
+            .try_fold(data, |data, migration| migration(data))
+            .map_err(unreadable)?;
+        bincode::deserialize(&data).map_err(unreadable)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn envelope_round_trip() {
+        let (instance, replay) = GameInstanceStorable::new_for_test(0);
+        let bytes = SaveEnvelope::new(&instance).unwrap().to_bytes().unwrap();
+        let envelope = SaveEnvelope::from_bytes(bytes);
+        assert_eq!(envelope.version(), SAVE_FORMAT_VERSION);
+        let loaded = envelope.into_instance().unwrap();
+        assert_eq!(
+            loaded.metadata(&replay).seed,
+            instance.metadata(&replay).seed
+        );
+        assert_eq!(loaded.game().player_coord(), instance.game().player_coord());
+        assert_eq!(loaded.game().tile_counts(), instance.game().tile_counts());
+    }
+
+    #[test]
+    fn save_without_envelope_is_version_0() {
+        let bytes = bincode::serialize(&GameInstanceStorable::new_for_test(0).0).unwrap();
+        let envelope = SaveEnvelope::from_bytes(bytes);
+        assert_eq!(envelope.version(), 0);
+        assert!(matches!(
+            envelope.into_instance(),
+            Err(LoadGameError::OlderVersion(0))
+        ));
+    }
+
+    /// Stands in for a change to the save format in which a header was removed from the front
+    /// of the serialized game
+    const TEST_HEADER: &[u8] = b"header";
+
+    fn remove_test_header(data: Vec<u8>) -> Result<Vec<u8>, bincode::Error> {
+        match data.strip_prefix(TEST_HEADER) {
+            Some(data) => Ok(data.to_vec()),
+            None => Err(Box::new(bincode::ErrorKind::Custom(format!(
+                "missing header"
+            )))),
+        }
+    }
+
+    #[test]
+    fn version_1_save_is_migrated() {
+        let (instance, replay) = GameInstanceStorable::new_for_test(0);
+        let mut envelope = SaveEnvelope::new(&instance).unwrap();
+        // version 1 serialized the replay between the game and the mist
+        let game_size = bincode::serialized_size(instance.game()).unwrap() as usize;
+        let (game, rest) = envelope.data.split_at(game_size);
+        envelope.data = [game, &bincode::serialize(&replay).unwrap(), rest].concat();
+        envelope.version = 1;
+        let envelope = SaveEnvelope::from_bytes(envelope.to_bytes().unwrap());
+        let loaded = envelope.into_instance().unwrap();
+        assert_eq!(loaded.game().player_coord(), instance.game().player_coord());
+        assert_eq!(loaded.game().tile_counts(), instance.game().tile_counts());
+        assert_eq!(
+            loaded.metadata(&replay).seed,
+            instance.metadata(&replay).seed
+        );
+    }
+
+    #[test]
+    fn older_version_is_migrated() {
+        let (instance, _replay) = GameInstanceStorable::new_for_test(0);
+        let mut envelope = SaveEnvelope::new(&instance).unwrap();
+        envelope.version = OLDEST_SUPPORTED_VERSION;
+        envelope.data = [TEST_HEADER, &envelope.data].concat();
+        let envelope = SaveEnvelope::from_bytes(envelope.to_bytes().unwrap());
+        let loaded = envelope
+            .into_instance_with_migrations(&[remove_test_header])
+            .unwrap();
+        assert_eq!(loaded.game().player_coord(), instance.game().player_coord());
+        assert_eq!(loaded.game().tile_counts(), instance.game().tile_counts());
+    }
+
+    #[test]
+    fn current_version_is_not_migrated() {
+        let (instance, _replay) = GameInstanceStorable::new_for_test(0);
+        let mut envelope = SaveEnvelope::new(&instance).unwrap();
+        envelope.version = OLDEST_SUPPORTED_VERSION + 1;
+        let loaded = envelope
+            .into_instance_with_migrations(&[remove_test_header])
+            .unwrap();
+        assert_eq!(loaded.game().player_coord(), instance.game().player_coord());
+    }
+
+    #[test]
+    fn newer_version_is_rejected() {
+        let mut envelope = SaveEnvelope::new(&GameInstanceStorable::new_for_test(0).0).unwrap();
+        envelope.version = SAVE_FORMAT_VERSION + 1;
+        let envelope = SaveEnvelope::from_bytes(envelope.to_bytes().unwrap());
+        assert!(matches!(
+            envelope.into_instance(),
+            Err(LoadGameError::NewerVersion(version)) if version == SAVE_FORMAT_VERSION + 1
+        ));
+    }
+}
@@ -0,0 +1,122 @@
+use boat_journey_game::{replay::Replay, Game, Npc};
+use serde::{Deserialize, Serialize};
+
+pub const NUM_SAVE_SLOTS: usize = 3;
+
+/// One of a fixed number of places a game can be saved
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SaveSlot(usize);
+
+impl SaveSlot {
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..NUM_SAVE_SLOTS).map(Self)
+    }
+
+    pub fn name(self) -> String {
+        format!("Slot {}", self.0 + 1)
+    }
+
+    /// The character used to choose the slot from a menu
+    pub fn hotkey(self) -> char {
+        std::char::from_digit(self.0 as u32 + 1, 10).unwrap()
+    }
+
+    /// The first slot uses the key that was used before there were multiple slots, so existing
+    /// files end up in the first slot
+    fn key(self, base: &str) -> String {
+        if self.0 == 0 {
+            base.to_string()
+        } else {
+            format!("{}-{}", base, self.0 + 1)
+        }
+    }
+
+    /// The key of the save game in this slot
+    pub fn save_game_key(self, base: &str) -> String {
+        self.key(base)
+    }
+
+    /// The key of the previous version of whatever is stored in this slot under `base`, i.e.
+    /// the save game or its replay
+    pub fn backup_key(self, base: &str) -> String {
+        format!("{}.bak", self.key(base))
+    }
+
+    pub fn metadata_key(self, base: &str) -> String {
+        format!("{}.meta", self.save_game_key(base))
+    }
+
+    /// The key of the replay of the game most recently played in this slot
+    pub fn replay_key(self, base: &str) -> String {
+        self.key(base)
+    }
+}
+
+/// Seconds since the unix epoch
+#[cfg(not(feature = "web"))]
+fn now() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+/// Seconds since the unix epoch. `SystemTime` isn't available in the browser.
+#[cfg(feature = "web")]
+fn now() -> u64 {
+    (js_sys::Date::now() / 1000.) as u64
+}
+
+/// A summary of a saved game, stored separately from the save itself so save slots can be
+/// listed without loading every game
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveMetadata {
+    pub day: u32,
+    pub num_turns: u64,
+    pub passengers: Vec<Npc>,
+    pub fuel: u32,
+    /// When the game was saved, in seconds since the unix epoch
+    pub timestamp: u64,
+    pub seed: u64,
+}
+
+impl SaveMetadata {
+    pub fn new(game: &Game, replay: &Replay) -> Self {
+        Self {
+            day: game.current_day(),
+            num_turns: game.victory_stats().num_turns,
+            passengers: game.passengers().to_vec(),
+            fuel: game.stats().fuel.current(),
+            timestamp: now(),
+            seed: replay.seed,
+        }
+    }
+
+    /// How long ago the game was saved, eg. "5 minutes ago"
+    fn age(&self) -> String {
+        let seconds = now().saturating_sub(self.timestamp);
+        let (count, unit) = if seconds < 60 {
+            return format!("just now");
+        } else if seconds < 60 * 60 {
+            (seconds / 60, "minute")
+        } else if seconds < 60 * 60 * 24 {
+            (seconds / (60 * 60), "hour")
+        } else {
+            (seconds / (60 * 60 * 24), "day")
+        };
+        let plural = if count == 1 { "" } else { "s" };
+        format!("{count} {unit}{plural} ago")
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "Day {}, {} turns, {} passengers, {} fuel, saved {}",
+            self.day,
+            self.num_turns,
+            self.passengers.len(),
+            self.fuel,
+            self.age()
+        )
+    }
+}
@@ -0,0 +1,69 @@
+use general_storage_static::{LoadRawError, RemoveError, Storage, StoreRawError};
+
+/// The methods of `Storage` which don't depend on a type parameter, so that storage backends
+/// can be used as trait objects
+trait RawStorage {
+    fn exists(&self, key: &str) -> bool;
+    fn clear(&mut self);
+    fn remove(&mut self, key: &str) -> Result<(), RemoveError>;
+    fn load_raw(&self, key: &str) -> Result<Vec<u8>, LoadRawError>;
+    fn store_raw(&mut self, key: &str, value: &[u8]) -> Result<(), StoreRawError>;
+}
+
+impl<S: Storage> RawStorage for S {
+    fn exists(&self, key: &str) -> bool {
+        Storage::exists(self, key)
+    }
+
+    fn clear(&mut self) {
+        Storage::clear(self)
+    }
+
+    fn remove(&mut self, key: &str) -> Result<(), RemoveError> {
+        Storage::remove(self, key)
+    }
+
+    fn load_raw(&self, key: &str) -> Result<Vec<u8>, LoadRawError> {
+        Storage::load_raw(self, key)
+    }
+
+    fn store_raw(&mut self, key: &str, value: &[u8]) -> Result<(), StoreRawError> {
+        Storage::store_raw(self, key, value)
+    }
+}
+
+/// Whichever storage backend the frontend chose, e.g. files written atomically on native
+/// platforms or local storage on the web
+pub struct StorageHandle(Box<dyn RawStorage>);
+
+impl StorageHandle {
+    pub fn new<S: Storage + 'static>(storage: S) -> Self {
+        Self(Box::new(storage))
+    }
+}
+
+impl Storage for StorageHandle {
+    fn exists<K: AsRef<str>>(&self, key: K) -> bool {
+        self.0.exists(key.as_ref())
+    }
+
+    fn clear(&mut self) {
+        self.0.clear()
+    }
+
+    fn remove<K: AsRef<str>>(&mut self, key: K) -> Result<(), RemoveError> {
+        self.0.remove(key.as_ref())
+    }
+
+    fn load_raw<K: AsRef<str>>(&self, key: K) -> Result<Vec<u8>, LoadRawError> {
+        self.0.load_raw(key.as_ref())
+    }
+
+    fn store_raw<K: AsRef<str>, V: AsRef<[u8]>>(
+        &mut self,
+        key: K,
+        value: V,
+    ) -> Result<(), StoreRawError> {
+        self.0.store_raw(key.as_ref(), value.as_ref())
+    }
+}
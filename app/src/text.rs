@@ -1,5 +1,5 @@
 use crate::game_loop::{AppCF, State};
-use boat_journey_game::GameOverReason;
+use boat_journey_game::{GameOverReason, Npc};
 use chargrid::{
     control_flow::*,
     prelude::*,
@@ -19,29 +19,41 @@ pub fn help(width: u32) -> AppCF<()> {
         string: s.to_string(),
         style: Style::plain_text().with_bold(true),
     };
-    text_component(
-        width,
-        vec![
-            b("Controls:\n\n"),
-            b("General\n"),
-            t("Wait: Space\n"),
-            t("Ability: 1-9\n"),
-            t("\n"),
-            b("On Foot\n"),
-            t("Walk: Arrow Keys\n"),
-            t("Drive Boat: e\n"),
-            t("\n"),
-            b("Driving Boat\n"),
-            t("Move: Forward/Backward\n"),
-            t("Turn: Left/Right\n"),
-            t("Leave Boat: e\n"),
-            b("\n\nTips:\n\n"),
-            t("- Walk into a door (+) to open it\n"),
-            t("- Walk into the wall next to a door to close the door\n"),
-            t("- Head to the inn when it gets dark\n"),
-        ],
-    )
-    .press_any_key()
+    let mut text = vec![
+        b("Controls:\n\n"),
+        b("General\n"),
+        t("Wait: Space\n"),
+        t("Ability: 1-9\n"),
+        t("Examine: x\n"),
+        t("World Map: m\n"),
+        t("Auto-explore: o\n"),
+        t("Travel: Examine then Enter\n"),
+        t("\n"),
+        b("On Foot\n"),
+        t("Walk: Arrow Keys or Click\n"),
+        t("Drive Boat: e or Click Controls\n"),
+        t("\n"),
+        b("Driving Boat\n"),
+        t("Move: Forward/Backward\n"),
+        t("Turn: Left/Right\n"),
+        t("Autopilot: p, or Examine then Enter\n"),
+        t("Leave Boat: e\n"),
+        b("\n\nTips:\n\n"),
+        t("- Walk into a door (+) to open it\n"),
+        t("- Walk into the wall next to a door to close the door\n"),
+        t("- Head to the inn when it gets dark\n"),
+    ];
+    text.push(b("\n\nPassengers:\n\n"));
+    for npc in Npc::all() {
+        let ability = npc.ability();
+        let range = match ability.range() {
+            Some(range) => format!(" (range {range})"),
+            None => String::new(),
+        };
+        text.push(b(&format!("{}: {}", npc.name(), ability.name())));
+        text.push(t(&format!("{range} - {}\n", ability.description())));
+    }
+    text_component(width, text).press_any_key()
 }
 
 pub fn loading(width: u32) -> AppCF<()> {
@@ -60,6 +72,27 @@ pub fn saving(width: u32) -> AppCF<()> {
     text_component(width, vec![t("Saving...")]).delay(Duration::from_millis(32))
 }
 
+pub fn load_failed(width: u32, message: String) -> AppCF<()> {
+    let t = |s: &str| StyledString {
+        string: s.to_string(),
+        style: Style::plain_text(),
+    };
+    let b = |s: &str| StyledString {
+        string: s.to_string(),
+        style: Style::plain_text().with_bold(true),
+    };
+    text_component(
+        width,
+        vec![
+            b("Save incompatible\n\n"),
+            t(&message),
+            t("\n\nThe save has been kept, but starting a new game in its slot will replace it.\n\n"),
+            t("Press any key..."),
+        ],
+    )
+    .press_any_key()
+}
+
 fn game_over_text(width: u32, reason: GameOverReason) -> CF<(), State> {
     let t = |s: &str| StyledString {
         string: s.to_string(),
@@ -87,6 +120,11 @@ fn game_over_text(width: u32, reason: GameOverReason) -> CF<(), State> {
             t("You fail to reach the ocean.\n\n"),
             t("You were caught in the soldier's blast.\n\n"),
         },
+        GameOverReason::Sunk => vec!{
+            t("You fail to reach the ocean.\n\n"),
+            t("The battered hull gives way and the boat sinks beneath the murky water.\n\n"),
+            t("You swim to the riverbank and watch the last bubbles rise from the place where your journey ended."),
+        },
     };
     text_component(width, text)
 }
@@ -0,0 +1,153 @@
+use crate::{
+    colour,
+    game_loop::{AppCF, State},
+};
+use boat_journey_game::{CellVisibility, Game, Tile, VisibleCellData};
+use chargrid::{control_flow::*, prelude::*, text::StyledString};
+use grid_2d::Grid;
+use rgb_int::Rgb24;
+
+/// What a cell of the world map represents. When several cells of the world are shown by a
+/// single cell of the map, the one that comes last here is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum MapCell {
+    Unknown,
+    Land,
+    Tree,
+    Water,
+    Town,
+    Stairs,
+    Shop,
+    Boat,
+    Player,
+}
+
+impl MapCell {
+    fn from_tile(tile: Tile) -> Self {
+        match tile {
+            Tile::Player => Self::Player,
+            Tile::BoatEdge | Tile::BoatFloor | Tile::Board | Tile::BoatControls => Self::Boat,
+            Tile::Shop => Self::Shop,
+            Tile::StairsDown | Tile::StairsUp => Self::Stairs,
+            Tile::Wall | Tile::DoorClosed | Tile::DoorOpen | Tile::UnimportantNpc => Self::Town,
+            Tile::Water1 | Tile::Water2 | Tile::Shallows => Self::Water,
+            Tile::Tree => Self::Tree,
+            Tile::Floor
+            | Tile::BurntFloor
+            | Tile::Rock
+            | Tile::Ghost
+            | Tile::Grave
+            | Tile::Npc(_)
+            | Tile::Junk
+            | Tile::Button
+            | Tile::ButtonPressed
+            | Tile::Beast => Self::Land,
+        }
+    }
+
+    fn from_cell_data(data: &VisibleCellData) -> Self {
+        let mut map_cell = Self::Unknown;
+        data.tiles.for_each_enumerate(|tile, _layer| {
+            if let Some(&tile) = tile.as_ref() {
+                map_cell = map_cell.max(Self::from_tile(tile));
+            }
+        });
+        map_cell
+    }
+
+    fn render_cell(self) -> RenderCell {
+        let (character, foreground, background) = match self {
+            Self::Unknown => (' ', Rgb24::new_grey(0), colour::MISTY_GREY),
+            Self::Land => ('.', Rgb24::new_grey(127), colour::MURKY_GREEN),
+            Self::Tree => ('♣', Rgb24::new(0, 187, 0), colour::MURKY_GREEN),
+            Self::Water => ('~', Rgb24::new(0, 127, 255), Rgb24::new(0, 0x20, 0x40)),
+            Self::Town => ('█', Rgb24::new_grey(187), colour::MURKY_GREEN),
+            Self::Stairs => ('>', Rgb24::new(255, 255, 0), colour::MURKY_GREEN),
+            Self::Shop => ('$', Rgb24::new(255, 187, 0), colour::MURKY_GREEN),
+            Self::Boat => ('B', Rgb24::new(187, 127, 63), Rgb24::new(0, 0x20, 0x40)),
+            Self::Player => ('@', Rgb24::new_grey(255), colour::MURKY_GREEN),
+        };
+        RenderCell::default()
+            .with_character(character)
+            .with_foreground(foreground.to_rgba32(255))
+            .with_background(background.to_rgba32(255))
+    }
+}
+
+/// Downsample the remembered cells of the current level so the whole level fits in `size`
+fn map_grid(game: &Game, size: Size) -> Grid<MapCell> {
+    let world_size = game.world_size();
+    let scale_x = (world_size.width() + size.width() - 1) / size.width();
+    let scale_y = (world_size.height() + size.height() - 1) / size.height();
+    let scale = scale_x.max(scale_y).max(1);
+    let map_size = Size::new(
+        (world_size.width() + scale - 1) / scale,
+        (world_size.height() + scale - 1) / scale,
+    );
+    let mut grid = Grid::new_copy(map_size, MapCell::Unknown);
+    for coord in world_size.coord_iter_row_major() {
+        if let CellVisibility::Previous(data) | CellVisibility::Current { data, .. } =
+            game.cell_visibility_at_coord(coord)
+        {
+            if let Some(cell) = grid.get_mut(coord / scale as i32) {
+                *cell = (*cell).max(MapCell::from_cell_data(data));
+            }
+        }
+    }
+    // The boat and player are always shown where they are now, even if they've moved since
+    // the cells around them were last seen
+    let current = game
+        .boat_coord()
+        .map(|coord| (coord, MapCell::Boat))
+        .into_iter()
+        .chain([(game.player_coord(), MapCell::Player)]);
+    for (coord, map_cell) in current {
+        if let Some(cell) = grid.get_mut(coord / scale as i32) {
+            *cell = map_cell;
+        }
+    }
+    grid
+}
+
+/// An overview of the whole of the current level, as far as it has been explored
+struct WorldMapComponent;
+
+impl Component for WorldMapComponent {
+    type Output = Option<()>;
+    type State = State;
+
+    fn render(&self, state: &Self::State, ctx: Ctx, fb: &mut FrameBuffer) {
+        let game = match state.game() {
+            Some(game) => game,
+            None => return,
+        };
+        let screen_size = ctx.bounding_box.size();
+        let available = Size::new(screen_size.width(), screen_size.height().saturating_sub(2));
+        let grid = map_grid(game, available);
+        let offset = (available.to_coord().unwrap() - grid.size().to_coord().unwrap()) / 2;
+        for (coord, map_cell) in grid.enumerate() {
+            fb.set_cell_relative_to_ctx(ctx, coord + offset, 0, map_cell.render_cell());
+        }
+        let footer = StyledString {
+            string: format!("@ you, B boat, $ inn, > stairs. Press any key to return."),
+            style: Style::plain_text(),
+        };
+        footer.render(&(), ctx.add_xy(1, screen_size.height() as i32 - 1), fb);
+    }
+
+    fn update(&mut self, _state: &mut Self::State, _ctx: Ctx, event: Event) -> Self::Output {
+        use chargrid::input::Input;
+        match event {
+            Event::Input(Input::Keyboard(_)) => Some(()),
+            _ => None,
+        }
+    }
+
+    fn size(&self, _state: &Self::State, ctx: Ctx) -> Size {
+        ctx.bounding_box.size()
+    }
+}
+
+pub fn world_map() -> AppCF<()> {
+    cf(WorldMapComponent)
+}
@@ -0,0 +1,53 @@
+use boat_journey_game::{replay::Replay, witness::Witness, Config};
+use std::fs::File;
+
+struct Args {
+    path: String,
+    omniscient: bool,
+}
+
+impl Args {
+    fn parser() -> impl meap::Parser<Item = Self> {
+        meap::let_map! {
+            let {
+                path = pos_req("PATH").desc("replay file");
+                omniscient = flag("omniscient").desc("enable omniscience");
+            } in {
+                Self { path, omniscient }
+            }
+        }
+    }
+}
+
+fn main() {
+    use meap::Parser;
+    let Args { path, omniscient } = Args::parser().with_help_default().parse_env_or_exit();
+    let file = File::open(&path).expect("failed to open replay file");
+    let replay: Replay = bincode::deserialize_from(file).expect("failed to parse replay file");
+    println!("RNG Seed: {}", replay.seed);
+    println!("Turns: {}", replay.num_turns());
+    let config = Config {
+        omniscient: if omniscient { Config::OMNISCIENT } else { None },
+        ..Default::default()
+    };
+    match replay.run(&config) {
+        Ok((game, witness)) => {
+            let game = game.inner_ref();
+            let state = match witness {
+                Witness::Running(_) => "running".to_string(),
+                Witness::GameOver(reason) => format!("game over ({:?})", reason),
+                Witness::Win(_) => "win".to_string(),
+                Witness::Menu(menu) => format!("menu ({:?})", menu.menu.image),
+                Witness::Aim(aim) => format!("aiming ({})", aim.npc.name()),
+            };
+            println!("Final state: {}", state);
+            println!("Player coord: {:?}", game.player_coord());
+            println!("Victory stats: {:?}", game.victory_stats());
+            println!("Messages: {:?}", game.messages());
+        }
+        Err(e) => {
+            eprintln!("Replay failed: {:?}", e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{EffectKind, Npc};
+
+/// How an ability chooses where it takes effect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Targeting {
+    /// Takes effect on the player as soon as it's used
+    Instant,
+    /// The player chooses a visible coordinate to target
+    Aimed,
+}
+
+/// What happens when an ability is used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbilityEffect {
+    /// Burn the ground and destroy anything destructible in a blob around the target
+    Blast {
+        radius: u32,
+    },
+    /// Teleport the player to the target, along with the boat if the player is driving
+    Blink,
+    /// Fully restore the player's health
+    Heal,
+    StartEffect {
+        kind: EffectKind,
+        turns: u32,
+    },
+}
+
+/// A passenger's ability. Each ability is a type implementing this trait, and `Npc::ability`
+/// maps each passenger to theirs, so adding a passenger only needs a new ability and a new arm
+/// in that match.
+pub trait Ability {
+    fn name(&self) -> String;
+    fn description(&self) -> String;
+    fn effect(&self) -> AbilityEffect;
+    fn targeting(&self) -> Targeting {
+        Targeting::Instant
+    }
+    /// The maximum distance from the player to the target of aimed abilities
+    fn range(&self) -> Option<u32> {
+        None
+    }
+    /// The number of times the ability can be used each day
+    fn charges(&self) -> u32 {
+        2
+    }
+}
+
+/// Turns that abilities which start an effect last for
+const EFFECT_TURNS: u32 = 30;
+
+pub struct Destroy;
+pub struct Blink;
+pub struct Fear;
+pub struct Phase;
+pub struct Heal;
+pub struct Sneak;
+pub struct Telescope;
+
+impl Ability for Destroy {
+    fn name(&self) -> String {
+        format!("Destroy")
+    }
+    fn description(&self) -> String {
+        format!("Destroy beasts and trees with an explosion.")
+    }
+    fn effect(&self) -> AbilityEffect {
+        AbilityEffect::Blast { radius: 4 }
+    }
+    fn targeting(&self) -> Targeting {
+        Targeting::Aimed
+    }
+    fn range(&self) -> Option<u32> {
+        Some(12)
+    }
+}
+
+impl Ability for Blink {
+    fn name(&self) -> String {
+        format!("Blink")
+    }
+    fn description(&self) -> String {
+        format!("Teleport to a visible location.")
+    }
+    fn effect(&self) -> AbilityEffect {
+        AbilityEffect::Blink
+    }
+    fn targeting(&self) -> Targeting {
+        Targeting::Aimed
+    }
+    fn range(&self) -> Option<u32> {
+        Some(10)
+    }
+}
+
+impl Ability for Fear {
+    fn name(&self) -> String {
+        format!("Fear")
+    }
+    fn description(&self) -> String {
+        format!("Beasts flee from you.")
+    }
+    fn effect(&self) -> AbilityEffect {
+        AbilityEffect::StartEffect {
+            kind: EffectKind::Fear,
+            turns: EFFECT_TURNS,
+        }
+    }
+}
+
+impl Ability for Phase {
+    fn name(&self) -> String {
+        format!("Phase")
+    }
+    fn description(&self) -> String {
+        format!("Walk through walls.")
+    }
+    fn effect(&self) -> AbilityEffect {
+        AbilityEffect::StartEffect {
+            kind: EffectKind::Phase,
+            turns: EFFECT_TURNS,
+        }
+    }
+}
+
+impl Ability for Heal {
+    fn name(&self) -> String {
+        format!("Heal")
+    }
+    fn description(&self) -> String {
+        format!("Restore all health.")
+    }
+    fn effect(&self) -> AbilityEffect {
+        AbilityEffect::Heal
+    }
+}
+
+impl Ability for Sneak {
+    fn name(&self) -> String {
+        format!("Sneak")
+    }
+    fn description(&self) -> String {
+        format!("Beasts don't notice you.")
+    }
+    fn effect(&self) -> AbilityEffect {
+        AbilityEffect::StartEffect {
+            kind: EffectKind::Sneak,
+            turns: EFFECT_TURNS,
+        }
+    }
+}
+
+impl Ability for Telescope {
+    fn name(&self) -> String {
+        format!("Telescope")
+    }
+    fn description(&self) -> String {
+        format!("See much further during the day.")
+    }
+    fn effect(&self) -> AbilityEffect {
+        AbilityEffect::StartEffect {
+            kind: EffectKind::Telescope,
+            turns: EFFECT_TURNS,
+        }
+    }
+}
+
+impl Npc {
+    pub fn ability(self) -> &'static dyn Ability {
+        match self {
+            Self::Soldier => &Destroy,
+            Self::Physicist => &Blink,
+            Self::Beast => &Fear,
+            Self::Ghost => &Phase,
+            Self::Surgeon => &Heal,
+            Self::Thief => &Sneak,
+            Self::Surveyor => &Telescope,
+        }
+    }
+}
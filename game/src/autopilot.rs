@@ -0,0 +1,483 @@
+use crate::{world::spatial::Layers, Boat, CardinalDirection, Direction, Game};
+use grid_2d::{Coord, Grid};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+/// The autopilot stops when there is less fuel than this
+pub const AUTOPILOT_LOW_FUEL: u32 = 20;
+
+/// Towns which are less than this much closer to the ocean than the boat (travelling through
+/// the water) are assumed to be the town the boat is already at
+const NEXT_TOWN_MIN_DISTANCE: u32 = 20;
+
+/// Water this close to a town's inn counts as being at the town
+const TOWN_RADIUS: i32 = 12;
+
+/// Water this close to a marked point counts as being at the point
+const POINT_RADIUS: i32 = 4;
+
+/// Water with land closer than this is avoided where possible, so the boat stays near the middle
+/// of channels
+const CLEARANCE: i32 = 2;
+
+/// The cost of crossing a cell of water that is close to land, relative to open water
+const NEAR_LAND_COST: u32 = 4;
+
+/// How many steps ahead of the boat to look when judging how well it's pointed
+const LOOKAHEAD: usize = 3;
+
+/// Give up after returning to the same position and heading this many times
+const MAX_REVISITS: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AutopilotDestination {
+    /// The nearest town downstream of the boat
+    NextTown,
+    Ocean,
+    Point(Coord),
+}
+
+impl AutopilotDestination {
+    pub fn name(self) -> String {
+        match self {
+            Self::NextTown => format!("the next town"),
+            Self::Ocean => format!("the ocean"),
+            Self::Point(_) => format!("the marked point"),
+        }
+    }
+}
+
+/// Why the autopilot stopped driving the boat
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AutopilotStop {
+    Arrived,
+    Danger,
+    LowFuel,
+    NotDriving,
+    NoRoute,
+    Stuck,
+}
+
+impl AutopilotStop {
+    pub fn message(self) -> String {
+        match self {
+            Self::Arrived => format!("The autopilot has reached its destination."),
+            Self::Danger => format!("The autopilot disengages. Something is nearby!"),
+            Self::LowFuel => format!("The autopilot disengages. Fuel is running low."),
+            Self::NotDriving => format!("You must be driving the boat to use the autopilot."),
+            Self::NoRoute => format!("The autopilot can't find a way there."),
+            Self::Stuck => format!("The autopilot can't find a way around an obstacle."),
+        }
+    }
+}
+
+/// Drives the boat towards a destination one step per turn. The route through the water is
+/// planned when the autopilot is engaged, and each step is checked against the boat's actual
+/// surroundings before it's taken.
+#[derive(Debug, Clone)]
+pub struct Autopilot {
+    destination: AutopilotDestination,
+    /// Cost of travelling from each cell of navigable water to the destination
+    distances: Grid<Option<u32>>,
+    low_fuel: u32,
+    stop_on_danger: bool,
+    /// Number of times the boat has been at each position and heading
+    visited: HashMap<(Coord, i64), u32>,
+}
+
+/// The direction to walk while driving to perform a boat manoeuvre
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Manoeuvre {
+    Forward,
+    RotateLeft,
+    RotateRight,
+    Backward,
+}
+
+impl Manoeuvre {
+    fn all() -> [Self; 4] {
+        [
+            Self::Forward,
+            Self::RotateLeft,
+            Self::RotateRight,
+            Self::Backward,
+        ]
+    }
+
+    fn direction(self) -> CardinalDirection {
+        match self {
+            Self::Forward => CardinalDirection::North,
+            Self::RotateLeft => CardinalDirection::West,
+            Self::RotateRight => CardinalDirection::East,
+            Self::Backward => CardinalDirection::South,
+        }
+    }
+
+    fn apply(self, boat: &Boat, boat_coord: Coord) -> (Boat, Coord) {
+        let step_radians = std::f64::consts::FRAC_PI_4;
+        match self {
+            Self::Forward => {
+                let (boat, delta) = boat.step();
+                (boat, boat_coord + delta)
+            }
+            Self::Backward => {
+                let (boat, delta) = boat.step_backwards();
+                (boat, boat_coord + delta)
+            }
+            Self::RotateLeft => (boat.add_heading(vector::Radians(-step_radians)), boat_coord),
+            Self::RotateRight => (boat.add_heading(vector::Radians(step_radians)), boat_coord),
+        }
+    }
+}
+
+/// Which of the 8 possible headings the boat has
+fn heading_index(boat: &Boat) -> i64 {
+    (boat.heading().0 / std::f64::consts::FRAC_PI_4)
+        .round()
+        .rem_euclid(8.) as i64
+}
+
+impl Autopilot {
+    pub fn new(game: &Game, destination: AutopilotDestination) -> Result<Self, AutopilotStop> {
+        let boat_coord = game.boat_coord().ok_or(AutopilotStop::NotDriving)?;
+        let destinations = game.autopilot_destination_coords(destination, boat_coord);
+        if destinations.is_empty() {
+            return Err(AutopilotStop::NoRoute);
+        }
+        let distances = game.navigable_distances(destinations, false);
+        if distances.get(boat_coord).cloned().flatten().is_none() {
+            return Err(AutopilotStop::NoRoute);
+        }
+        Ok(Self {
+            destination,
+            distances,
+            low_fuel: AUTOPILOT_LOW_FUEL,
+            stop_on_danger: true,
+            visited: HashMap::new(),
+        })
+    }
+
+    /// Keep driving until the fuel drops below `low_fuel`
+    pub fn with_low_fuel(self, low_fuel: u32) -> Self {
+        Self { low_fuel, ..self }
+    }
+
+    /// Keep driving even when a beast or ghost is in view
+    pub fn ignoring_danger(self) -> Self {
+        Self {
+            stop_on_danger: false,
+            ..self
+        }
+    }
+
+    pub fn destination(&self) -> AutopilotDestination {
+        self.destination
+    }
+
+    fn distance(&self, coord: Coord) -> Option<u32> {
+        self.distances.get(coord).cloned().flatten()
+    }
+
+    /// How far the boat would be from the destination a few steps after it's in a given
+    /// position, if it kept going straight. Lower is better.
+    fn score(&self, boat: &Boat, boat_coord: Coord) -> u32 {
+        let mut boat = boat.clone();
+        let mut coord = boat_coord;
+        for _ in 0..LOOKAHEAD {
+            let (next, delta) = boat.step();
+            boat = next;
+            coord = coord + delta;
+        }
+        let here = self.distance(boat_coord).unwrap_or(u32::MAX);
+        match self.distance(coord) {
+            Some(ahead) => here.saturating_add(ahead),
+            // pointing at land
+            None => here.saturating_mul(2).saturating_add(LOOKAHEAD as u32 * 2),
+        }
+    }
+
+    /// The direction to walk while driving to move the boat one step closer to the
+    /// destination. This is the input that would be passed to `Game::handle_input` as
+    /// `Input::Walk`. Returns an error if the autopilot should stop.
+    pub fn next_direction(&mut self, game: &Game) -> Result<CardinalDirection, AutopilotStop> {
+        if !game.is_driving() {
+            return Err(AutopilotStop::NotDriving);
+        }
+        let (boat, boat_coord) = game.boat().ok_or(AutopilotStop::NotDriving)?;
+        if self.distance(boat_coord) == Some(0) {
+            return Err(AutopilotStop::Arrived);
+        }
+        if self.stop_on_danger && game.is_enemy_visible() {
+            return Err(AutopilotStop::Danger);
+        }
+        if game.stats().fuel.current() < self.low_fuel {
+            return Err(AutopilotStop::LowFuel);
+        }
+        let visits = self
+            .visited
+            .entry((boat_coord, heading_index(boat)))
+            .or_insert(0);
+        *visits += 1;
+        if *visits > MAX_REVISITS {
+            return Err(AutopilotStop::Stuck);
+        }
+        let current_score = self.score(boat, boat_coord);
+        let mut best: Option<(u32, Manoeuvre)> = None;
+        for manoeuvre in Manoeuvre::all() {
+            let (boat_next, coord_next) = manoeuvre.apply(boat, boat_coord);
+            if self.distance(coord_next).is_none() || !game.can_boat_occupy(&boat_next, coord_next)
+            {
+                continue;
+            }
+            let score = self.score(&boat_next, coord_next);
+            // only reverse when there is no other way to make progress
+            if manoeuvre == Manoeuvre::Backward && (best.is_some() || score >= current_score) {
+                continue;
+            }
+            // earlier manoeuvres are preferred when scores are equal
+            if best
+                .map(|(best_score, _)| score < best_score)
+                .unwrap_or(true)
+            {
+                best = Some((score, manoeuvre));
+            }
+        }
+        best.map(|(_, manoeuvre)| manoeuvre.direction())
+            .ok_or(AutopilotStop::Stuck)
+    }
+}
+
+impl Game {
+    /// Returns true if the boat could float on the cell, ignoring the boat itself. Closed gates
+    /// are treated as open water if `through_gates` is set.
+    fn is_navigable_water(&self, coord: Coord, through_gates: bool) -> bool {
+        let components = &self.world.components;
+        let is_clear = |entity: Option<_>| match entity {
+            None => true,
+            Some(entity) => {
+                components.part_of_boat.contains(entity)
+                    || (through_gates && components.gate.contains(entity))
+            }
+        };
+        match self.world.spatial_table.layers_at(coord) {
+            Some(&Layers {
+                water: Some(_),
+                floor,
+                feature,
+                ..
+            }) => is_clear(floor) && is_clear(feature),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the cell and everything near it is navigable
+    fn is_open_water(&self, coord: Coord, through_gates: bool) -> bool {
+        (-CLEARANCE..=CLEARANCE).all(|dy| {
+            (-CLEARANCE..=CLEARANCE)
+                .all(|dx| self.is_navigable_water(coord + Coord::new(dx, dy), through_gates))
+        })
+    }
+
+    fn autopilot_destination_coords(
+        &self,
+        destination: AutopilotDestination,
+        boat_coord: Coord,
+    ) -> Vec<Coord> {
+        let near = |centre: Coord, radius: i32| {
+            self.world_size()
+                .coord_iter_row_major()
+                .filter(move |&coord| {
+                    let delta = coord - centre;
+                    delta.x.abs() <= radius && delta.y.abs() <= radius
+                })
+                .filter(|&coord| self.is_navigable_water(coord, false))
+                .collect::<Vec<_>>()
+        };
+        match destination {
+            AutopilotDestination::Ocean => self
+                .world
+                .components
+                .ocean
+                .entities()
+                .filter_map(|entity| self.world.spatial_table.coord_of(entity))
+                .filter(|&coord| self.is_navigable_water(coord, false))
+                .collect(),
+            AutopilotDestination::NextTown => {
+                // The river flows towards the ocean, so the towns downstream of the boat are the
+                // ones closer to the ocean by water, and the next town is the furthest of them.
+                // Gates between the boat and the ocean don't change which way the river flows.
+                let to_ocean = self.navigable_distances(
+                    self.autopilot_destination_coords(AutopilotDestination::Ocean, boat_coord),
+                    true,
+                );
+                let ocean_distance = |coord: Coord| to_ocean.get(coord).cloned().flatten();
+                let boat_distance = match ocean_distance(boat_coord) {
+                    Some(distance) => distance,
+                    None => return Vec::new(),
+                };
+                self.world
+                    .components
+                    .shop
+                    .entities()
+                    .filter_map(|entity| self.world.spatial_table.coord_of(entity))
+                    .filter_map(|shop_coord| {
+                        let water = near(shop_coord, TOWN_RADIUS);
+                        let distance = water.iter().filter_map(|&c| ocean_distance(c)).min()?;
+                        (distance + NEXT_TOWN_MIN_DISTANCE < boat_distance)
+                            .then_some((distance, water))
+                    })
+                    .max_by_key(|(distance, _)| *distance)
+                    .map(|(_, water)| water)
+                    .unwrap_or_default()
+            }
+            AutopilotDestination::Point(coord) => near(coord, POINT_RADIUS),
+        }
+    }
+
+    /// Cost of travelling from each cell of navigable water to the nearest destination.
+    /// Water close to land costs more to cross so routes keep to the middle of channels.
+    fn navigable_distances(
+        &self,
+        destinations: Vec<Coord>,
+        through_gates: bool,
+    ) -> Grid<Option<u32>> {
+        let mut distances = Grid::new_copy(self.world_size(), None);
+        let mut to_visit = BinaryHeap::new();
+        for coord in destinations {
+            if let Some(cell) = distances.get_mut(coord) {
+                *cell = Some(0);
+                to_visit.push(Reverse((0, coord.x, coord.y)));
+            }
+        }
+        while let Some(Reverse((distance, x, y))) = to_visit.pop() {
+            let coord = Coord::new(x, y);
+            if distances.get(coord).cloned().flatten() != Some(distance) {
+                // already reached more cheaply
+                continue;
+            }
+            for direction in Direction::all() {
+                let nei_coord = coord + direction.coord();
+                if !self.is_navigable_water(nei_coord, through_gates) {
+                    continue;
+                }
+                let cost = if self.is_open_water(nei_coord, through_gates) {
+                    1
+                } else {
+                    NEAR_LAND_COST
+                };
+                let nei_distance = distance + cost;
+                if let Some(cell) = distances.get_mut(nei_coord) {
+                    if cell.map(|d| nei_distance < d).unwrap_or(true) {
+                        *cell = Some(nei_distance);
+                        to_visit.push(Reverse((nei_distance, nei_coord.x, nei_coord.y)));
+                    }
+                }
+            }
+        }
+        distances
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Config;
+
+    /// A new game with the player driving the boat and every gate on the river open
+    fn driving_game() -> Game {
+        let mut game = Game::new(&Config::default(), Vec::new(), 0);
+        game.driving = true;
+        let gates = game.world.components.gate.entities().collect::<Vec<_>>();
+        for entity in gates {
+            game.world.components.remove_entity(entity);
+            game.world.spatial_table.remove(entity);
+        }
+        game
+    }
+
+    #[test]
+    fn route_leads_to_ocean() {
+        let game = driving_game();
+        let boat_coord = game.boat_coord().unwrap();
+        let autopilot = Autopilot::new(&game, AutopilotDestination::Ocean).unwrap();
+        assert!(autopilot.distance(boat_coord).unwrap() > 0);
+        for coord in game.autopilot_destination_coords(AutopilotDestination::Ocean, boat_coord) {
+            assert_eq!(autopilot.distance(coord), Some(0));
+        }
+    }
+
+    #[test]
+    fn next_town_is_downstream() {
+        let game = driving_game();
+        let boat_coord = game.boat_coord().unwrap();
+        let to_ocean = game.navigable_distances(
+            game.autopilot_destination_coords(AutopilotDestination::Ocean, boat_coord),
+            false,
+        );
+        let ocean_distance = |coord: Coord| to_ocean.get(coord).cloned().flatten();
+        let town_distance = game
+            .autopilot_destination_coords(AutopilotDestination::NextTown, boat_coord)
+            .into_iter()
+            .filter_map(ocean_distance)
+            .min()
+            .unwrap();
+        assert!(town_distance + NEXT_TOWN_MIN_DISTANCE < ocean_distance(boat_coord).unwrap());
+    }
+
+    #[test]
+    fn next_town_before_gates_open() {
+        let mut game = Game::new(&Config::default(), Vec::new(), 0);
+        game.driving = true;
+        assert_eq!(
+            Autopilot::new(&game, AutopilotDestination::Ocean).err(),
+            Some(AutopilotStop::NoRoute)
+        );
+        assert!(Autopilot::new(&game, AutopilotDestination::NextTown).is_ok());
+    }
+
+    #[test]
+    fn stops_when_not_driving() {
+        let mut game = driving_game();
+        let mut autopilot = Autopilot::new(&game, AutopilotDestination::Ocean).unwrap();
+        game.driving = false;
+        assert_eq!(
+            autopilot.next_direction(&game),
+            Err(AutopilotStop::NotDriving)
+        );
+    }
+
+    #[test]
+    fn stops_on_arrival() {
+        let game = driving_game();
+        let boat_coord = game.boat_coord().unwrap();
+        let mut autopilot = Autopilot::new(&game, AutopilotDestination::Point(boat_coord)).unwrap();
+        assert_eq!(autopilot.next_direction(&game), Err(AutopilotStop::Arrived));
+    }
+
+    #[test]
+    fn stops_on_low_fuel() {
+        let game = driving_game();
+        let mut autopilot = Autopilot::new(&game, AutopilotDestination::Ocean)
+            .unwrap()
+            .ignoring_danger()
+            .with_low_fuel(u32::MAX);
+        assert_eq!(autopilot.next_direction(&game), Err(AutopilotStop::LowFuel));
+    }
+
+    #[test]
+    fn stops_when_stuck() {
+        let game = driving_game();
+        let mut autopilot = Autopilot::new(&game, AutopilotDestination::Ocean)
+            .unwrap()
+            .ignoring_danger()
+            .with_low_fuel(0);
+        // the boat never moves, so the autopilot keeps finding itself in the same place
+        for _ in 0..MAX_REVISITS {
+            assert!(autopilot.next_direction(&game).is_ok());
+        }
+        assert_eq!(autopilot.next_direction(&game), Err(AutopilotStop::Stuck));
+    }
+}
@@ -0,0 +1,375 @@
+use crate::{
+    world::spatial::Layers, EffectKind, Game, Junk, Npc, BEAST_APPROACH_DISTANCE,
+    BEAST_FLEE_DISTANCE, GHOST_SPAWN_DISTANCE,
+};
+use coord_2d::Coord;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+/// Usage of each command understood by `DebugCommand::parse`
+pub const DEBUG_COMMANDS: &[&str] = &[
+    "teleport X Y",
+    "spawn beast|ghost|junk",
+    "set health|fuel|day|junk|hull|PASSENGER VALUE",
+    "passenger NAME",
+    "omniscient",
+    "night",
+    "dungeon INDEX",
+];
+
+/// Things are spawned no further than this from the player
+const SPAWN_RADIUS: i32 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DebugSpawn {
+    Beast,
+    Ghost,
+    Junk,
+}
+
+/// A meter that can be set from the debug console
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DebugMeter {
+    Health,
+    Fuel,
+    Day,
+    Junk,
+    HullIntegrity,
+    /// Uses of a passenger's ability remaining today
+    Actions(Npc),
+}
+
+/// Changes to the game made directly rather than by playing, to quickly get the game into a
+/// state that needs testing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DebugCommand {
+    Teleport(Coord),
+    Spawn(DebugSpawn),
+    /// Setting a meter above its maximum raises the maximum
+    SetMeter(DebugMeter, u32),
+    AddPassenger(Npc),
+    ToggleOmniscient,
+    SkipToNight,
+    EnterDungeon(usize),
+}
+
+/// How beasts react to the player, for debug overlays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BeastBehaviour {
+    /// Beasts up to `range` steps from the player move towards them
+    Approach { range: u32 },
+    /// Beasts up to `range` steps from the player move away from them
+    Flee { range: u32 },
+    /// Beasts don't notice the player
+    Ignore,
+}
+
+#[derive(Debug, Clone)]
+pub enum DebugError {
+    /// Debug commands are only accepted when `Config::debug` is set
+    Disabled,
+    UnknownCommand(String),
+    /// The arguments didn't match the command's usage
+    Usage(&'static str),
+    OutOfBounds(Coord),
+    Occupied(Coord),
+    NoSpaceToSpawn,
+    NotAPassenger(Npc),
+    AlreadyAPassenger(Npc),
+    NoEmptySeats,
+    NoSuchDungeon(usize),
+}
+
+impl DebugError {
+    pub fn message(&self) -> String {
+        match self {
+            Self::Disabled => format!("Debug commands are disabled."),
+            Self::UnknownCommand(command) => format!("Unknown command \"{}\".", command),
+            Self::Usage(usage) => format!("Usage: {}", usage),
+            Self::OutOfBounds(coord) => format!("({}, {}) is out of bounds.", coord.x, coord.y),
+            Self::Occupied(coord) => format!("({}, {}) is occupied.", coord.x, coord.y),
+            Self::NoSpaceToSpawn => format!("There's no space near the player."),
+            Self::NotAPassenger(npc) => format!("The {} is not a passenger.", npc.name()),
+            Self::AlreadyAPassenger(npc) => format!("The {} is already a passenger.", npc.name()),
+            Self::NoEmptySeats => format!("The boat has no more seats."),
+            Self::NoSuchDungeon(index) => format!("There is no dungeon {}.", index),
+        }
+    }
+}
+
+fn parse_npc(name: &str) -> Option<Npc> {
+    Npc::all()
+        .into_iter()
+        .find(|npc| npc.name().eq_ignore_ascii_case(name))
+}
+
+impl DebugCommand {
+    /// Parse a line typed into the debug console. See `DEBUG_COMMANDS` for the syntax.
+    pub fn parse(line: &str) -> Result<Self, DebugError> {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        let (&command, args) = words
+            .split_first()
+            .ok_or_else(|| DebugError::UnknownCommand(String::new()))?;
+        let (usage, command) = match command {
+            "teleport" => (DEBUG_COMMANDS[0], parse_teleport(args)),
+            "spawn" => (DEBUG_COMMANDS[1], parse_spawn(args)),
+            "set" => (DEBUG_COMMANDS[2], parse_set(args)),
+            "passenger" => (
+                DEBUG_COMMANDS[3],
+                match args {
+                    [name] => parse_npc(name).map(Self::AddPassenger),
+                    _ => None,
+                },
+            ),
+            "omniscient" => (
+                DEBUG_COMMANDS[4],
+                args.is_empty().then_some(Self::ToggleOmniscient),
+            ),
+            "night" => (
+                DEBUG_COMMANDS[5],
+                args.is_empty().then_some(Self::SkipToNight),
+            ),
+            "dungeon" => (
+                DEBUG_COMMANDS[6],
+                match args {
+                    [index] => index.parse().ok().map(Self::EnterDungeon),
+                    _ => None,
+                },
+            ),
+            other => return Err(DebugError::UnknownCommand(other.to_string())),
+        };
+        command.ok_or(DebugError::Usage(usage))
+    }
+}
+
+fn parse_teleport(args: &[&str]) -> Option<DebugCommand> {
+    match args {
+        [x, y] => Some(DebugCommand::Teleport(Coord::new(
+            x.parse().ok()?,
+            y.parse().ok()?,
+        ))),
+        _ => None,
+    }
+}
+
+fn parse_spawn(args: &[&str]) -> Option<DebugCommand> {
+    let spawn = match args {
+        ["beast"] => DebugSpawn::Beast,
+        ["ghost"] => DebugSpawn::Ghost,
+        ["junk"] => DebugSpawn::Junk,
+        _ => return None,
+    };
+    Some(DebugCommand::Spawn(spawn))
+}
+
+fn parse_set(args: &[&str]) -> Option<DebugCommand> {
+    let (meter, value) = match args {
+        [meter, value] => (*meter, value.parse().ok()?),
+        _ => return None,
+    };
+    let meter = match meter {
+        "health" => DebugMeter::Health,
+        "fuel" => DebugMeter::Fuel,
+        "day" => DebugMeter::Day,
+        "junk" => DebugMeter::Junk,
+        "hull" => DebugMeter::HullIntegrity,
+        other => DebugMeter::Actions(parse_npc(other)?),
+    };
+    Some(DebugCommand::SetMeter(meter, value))
+}
+
+impl Game {
+    pub fn is_omniscient(&self) -> bool {
+        self.omniscient
+    }
+
+    pub fn beast_behaviour(&self) -> BeastBehaviour {
+        if self.is_effect_active(EffectKind::Sneak) {
+            BeastBehaviour::Ignore
+        } else if self.is_effect_active(EffectKind::Fear) {
+            BeastBehaviour::Flee {
+                range: BEAST_FLEE_DISTANCE,
+            }
+        } else {
+            BeastBehaviour::Approach {
+                range: BEAST_APPROACH_DISTANCE,
+            }
+        }
+    }
+
+    /// The value at a coord of the distance map beasts used to choose where to move on the most
+    /// recent turn. Each beast moves to whichever neighbour has the lowest value.
+    pub fn beast_distance(&self, coord: Coord) -> Option<u32> {
+        // the distance map isn't updated while beasts ignore the player
+        if self.beast_behaviour() == BeastBehaviour::Ignore {
+            return None;
+        }
+        self.world.distance_map.distance(coord)
+    }
+
+    /// Ghosts appear this far from the player while they are outside at night
+    pub fn ghost_spawn_distance(&self) -> f64 {
+        GHOST_SPAWN_DISTANCE
+    }
+
+    /// Apply a debug command. Time doesn't pass, but the visible area is updated.
+    pub(crate) fn handle_debug_command(
+        &mut self,
+        command: DebugCommand,
+    ) -> Result<String, DebugError> {
+        let message = match command {
+            DebugCommand::Teleport(coord) => self.debug_teleport(coord)?,
+            DebugCommand::Spawn(spawn) => self.debug_spawn(spawn)?,
+            DebugCommand::SetMeter(meter, value) => self.debug_set_meter(meter, value)?,
+            DebugCommand::AddPassenger(npc) => self.debug_add_passenger(npc)?,
+            DebugCommand::ToggleOmniscient => {
+                self.omniscient = !self.omniscient;
+                format!(
+                    "Omniscience {}.",
+                    if self.omniscient { "on" } else { "off" }
+                )
+            }
+            DebugCommand::SkipToNight => {
+                if !self.stats.day.is_empty() {
+                    self.stats.day.set_current(0);
+                    self.change_weather();
+                }
+                format!("It is night.")
+            }
+            DebugCommand::EnterDungeon(index) => {
+                if index >= self.dungeons.len() {
+                    return Err(DebugError::NoSuchDungeon(index));
+                }
+                if self.is_in_dungeon() {
+                    self.exit_dungeon();
+                }
+                self.driving = false;
+                self.enter_dungeon(index);
+                format!("Entered dungeon {}.", index)
+            }
+        };
+        self.update_visibility();
+        Ok(message)
+    }
+
+    fn debug_teleport(&mut self, coord: Coord) -> Result<String, DebugError> {
+        if !coord.is_valid(self.world_size()) {
+            return Err(DebugError::OutOfBounds(coord));
+        }
+        self.world
+            .spatial_table
+            .update_coord(self.player_entity, coord)
+            .map_err(|_| DebugError::Occupied(coord))?;
+        self.driving = false;
+        Ok(format!("Teleported to ({}, {}).", coord.x, coord.y))
+    }
+
+    /// Returns true if the given kind of thing could be spawned at a coord
+    fn is_debug_spawn_coord(&self, coord: Coord, spawn: DebugSpawn) -> bool {
+        let layers = match self.world.spatial_table.layers_at(coord) {
+            Some(layers) => layers,
+            None => return false,
+        };
+        if let Some(feature) = layers.feature {
+            if self.world.components.solid.contains(feature) {
+                return false;
+            }
+        }
+        let is_deep_water = matches!(
+            layers,
+            Layers {
+                water: Some(_),
+                floor: None,
+                ..
+            }
+        );
+        match spawn {
+            DebugSpawn::Beast => layers.character.is_none() && !is_deep_water,
+            DebugSpawn::Ghost => layers.character.is_none(),
+            DebugSpawn::Junk => layers.item.is_none() && !is_deep_water,
+        }
+    }
+
+    /// The closest coord to the player (other than the player's own) where something can be
+    /// spawned
+    fn debug_spawn_coord(&self, spawn: DebugSpawn) -> Option<Coord> {
+        let player_coord = self.player_coord();
+        (1..=SPAWN_RADIUS).find_map(|radius| {
+            (-radius..=radius)
+                .flat_map(|dy| (-radius..=radius).map(move |dx| Coord::new(dx, dy)))
+                .filter(|delta| delta.x.abs() == radius || delta.y.abs() == radius)
+                .map(|delta| player_coord + delta)
+                .find(|&coord| self.is_debug_spawn_coord(coord, spawn))
+        })
+    }
+
+    fn debug_spawn(&mut self, spawn: DebugSpawn) -> Result<String, DebugError> {
+        let coord = self
+            .debug_spawn_coord(spawn)
+            .ok_or(DebugError::NoSpaceToSpawn)?;
+        let name = match spawn {
+            DebugSpawn::Beast => {
+                self.world.spawn_beast(coord);
+                format!("beast")
+            }
+            DebugSpawn::Ghost => {
+                self.world.spawn_ghost(coord);
+                format!("ghost")
+            }
+            DebugSpawn::Junk => {
+                let junk = *Junk::all().choose(&mut self.rng).unwrap();
+                self.world.spawn_junk(coord, junk);
+                junk.name()
+            }
+        };
+        Ok(format!("Spawned {} at ({}, {}).", name, coord.x, coord.y))
+    }
+
+    fn debug_set_meter(&mut self, meter: DebugMeter, value: u32) -> Result<String, DebugError> {
+        let (name, meter) = match meter {
+            DebugMeter::Health => (format!("Health"), &mut self.stats.health),
+            DebugMeter::Fuel => (format!("Fuel"), &mut self.stats.fuel),
+            DebugMeter::Day => (format!("Daylight"), &mut self.stats.day),
+            DebugMeter::Junk => (format!("Junk"), &mut self.stats.junk),
+            DebugMeter::HullIntegrity => {
+                (format!("Hull integrity"), &mut self.stats.hull_integrity)
+            }
+            DebugMeter::Actions(npc) => (
+                format!("{} actions", npc.name()),
+                self.npc_actions
+                    .get_mut(&npc)
+                    .ok_or(DebugError::NotAPassenger(npc))?,
+            ),
+        };
+        if value > meter.max() {
+            meter.set_max(value);
+        }
+        meter.set_current(value);
+        let (current, max) = meter.current_and_max();
+        Ok(format!("{} is {}/{}.", name, current, max))
+    }
+
+    fn debug_add_passenger(&mut self, npc: Npc) -> Result<String, DebugError> {
+        if self.passengers.contains(&npc) {
+            return Err(DebugError::AlreadyAPassenger(npc));
+        }
+        if self.passengers.len() as u32 >= self.hull.num_seats() {
+            return Err(DebugError::NoEmptySeats);
+        }
+        // remove the npc from the world so they can't be picked up a second time
+        let entity = self
+            .world
+            .components
+            .npc
+            .iter()
+            .find_map(|(entity, &other)| (other == npc).then_some(entity));
+        if let Some(entity) = entity {
+            self.world.components.remove_entity(entity);
+            self.world.spatial_table.remove(entity);
+        }
+        self.has_talked_to_npc = true;
+        self.add_npc_to_passengers_(npc);
+        self.num_seats = self.num_seats.max(self.passengers.len() as u32);
+        Ok(format!("The {} boarded the boat.", npc.name()))
+    }
+}
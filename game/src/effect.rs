@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EffectKind {
+    Fear,
+    Phase,
+    Sneak,
+    Telescope,
+}
+
+/// What happens when an effect is applied while it's already active
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stacking {
+    /// The new duration is added to the remaining duration
+    Extend,
+    /// The effect lasts for whichever is longer out of the remaining and new durations
+    Refresh,
+}
+
+impl EffectKind {
+    pub fn all() -> Vec<Self> {
+        vec![Self::Fear, Self::Phase, Self::Sneak, Self::Telescope]
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Self::Fear => format!("Fear"),
+            Self::Phase => format!("Phase"),
+            Self::Sneak => format!("Sneak"),
+            Self::Telescope => format!("Telescope"),
+        }
+    }
+
+    pub fn stacking(self) -> Stacking {
+        match self {
+            Self::Fear | Self::Phase | Self::Sneak => Stacking::Extend,
+            Self::Telescope => Stacking::Refresh,
+        }
+    }
+
+    pub fn start_message(self, turns: u32) -> String {
+        match self {
+            Self::Fear => format!("Beasts will flee from you for the next {turns} turns."),
+            Self::Phase => {
+                format!("You can walk through walls (but not enemies) for the next {turns} turns.")
+            }
+            Self::Sneak => format!("Enemies will not react to you for the next {turns} turns."),
+            Self::Telescope => format!("You borrow the surveyor's telescope for {turns} turns"),
+        }
+    }
+
+    pub fn expiry_message(self) -> String {
+        match self {
+            Self::Fear => format!("Beasts no longer flee from you."),
+            Self::Phase => format!("You can no longer walk through walls."),
+            Self::Sneak => format!("You are no longer undetectable by beasts."),
+            Self::Telescope => format!("You give the surveyor back their telescope."),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Effect {
+    pub kind: EffectKind,
+    pub remaining: u32,
+}
+
+/// The effects currently applied to the player, in the order they were started
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Effects {
+    active: Vec<Effect>,
+}
+
+impl Effects {
+    pub fn is_active(&self, kind: EffectKind) -> bool {
+        self.active.iter().any(|effect| effect.kind == kind)
+    }
+
+    /// The number of turns until the effect expires, or 0 if it isn't active
+    pub fn remaining(&self, kind: EffectKind) -> u32 {
+        self.active
+            .iter()
+            .find(|effect| effect.kind == kind)
+            .map(|effect| effect.remaining)
+            .unwrap_or(0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Effect> {
+        self.active.iter()
+    }
+
+    /// Returns true if the effect wasn't already active
+    pub(crate) fn apply(&mut self, kind: EffectKind, turns: u32) -> bool {
+        if turns == 0 {
+            return false;
+        }
+        if let Some(effect) = self.active.iter_mut().find(|effect| effect.kind == kind) {
+            effect.remaining = match kind.stacking() {
+                Stacking::Extend => effect.remaining + turns,
+                Stacking::Refresh => effect.remaining.max(turns),
+            };
+            false
+        } else {
+            self.active.push(Effect {
+                kind,
+                remaining: turns,
+            });
+            true
+        }
+    }
+
+    /// Count down all active effects, returning the kinds of effects which expired
+    pub(crate) fn tick(&mut self) -> Vec<EffectKind> {
+        let mut expired = Vec::new();
+        for effect in self.active.iter_mut() {
+            effect.remaining -= 1;
+            if effect.remaining == 0 {
+                expired.push(effect.kind);
+            }
+        }
+        self.active.retain(|effect| effect.remaining > 0);
+        expired
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn extend_adds_durations() {
+        let mut effects = Effects::default();
+        assert!(effects.apply(EffectKind::Fear, 5));
+        assert!(!effects.apply(EffectKind::Fear, 3));
+        assert_eq!(effects.remaining(EffectKind::Fear), 8);
+    }
+
+    #[test]
+    fn refresh_keeps_longer_duration() {
+        let mut effects = Effects::default();
+        assert!(effects.apply(EffectKind::Telescope, 5));
+        assert!(!effects.apply(EffectKind::Telescope, 3));
+        assert_eq!(effects.remaining(EffectKind::Telescope), 5);
+        assert!(!effects.apply(EffectKind::Telescope, 7));
+        assert_eq!(effects.remaining(EffectKind::Telescope), 7);
+    }
+
+    #[test]
+    fn zero_turns_does_nothing() {
+        let mut effects = Effects::default();
+        assert!(!effects.apply(EffectKind::Sneak, 0));
+        assert!(!effects.is_active(EffectKind::Sneak));
+    }
+
+    #[test]
+    fn query_and_expiry() {
+        let mut effects = Effects::default();
+        effects.apply(EffectKind::Phase, 1);
+        effects.apply(EffectKind::Sneak, 2);
+        assert!(effects.is_active(EffectKind::Phase));
+        assert!(!effects.is_active(EffectKind::Fear));
+        assert_eq!(effects.remaining(EffectKind::Fear), 0);
+        let kinds = effects.iter().map(|effect| effect.kind).collect::<Vec<_>>();
+        assert_eq!(kinds, vec![EffectKind::Phase, EffectKind::Sneak]);
+        assert_eq!(effects.tick(), vec![EffectKind::Phase]);
+        assert!(!effects.is_active(EffectKind::Phase));
+        assert_eq!(effects.remaining(EffectKind::Sneak), 1);
+        assert_eq!(effects.tick(), vec![EffectKind::Sneak]);
+        assert_eq!(effects.iter().count(), 0);
+    }
+}
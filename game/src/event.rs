@@ -0,0 +1,86 @@
+use crate::{EffectKind, Junk, Npc, SoundEvent, Upgrade, Weather};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DamageSource {
+    Ghost,
+    Beast,
+}
+
+/// Something that happened during a turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameEvent {
+    DamageTaken(DamageSource),
+    ItemPickedUp(Junk),
+    PassengerBoarded(Npc),
+    EffectStarted { kind: EffectKind, turns: u32 },
+    EffectExpired(EffectKind),
+    DoorOpened,
+    DoorClosed,
+    DayStarted(u32),
+    WeatherChanged(Weather),
+    BoatMoved,
+    BoatCrashed,
+    HullScraped,
+    GateOpened,
+    Healed,
+    Blinked,
+    Blast,
+    BeastDestroyed,
+    FuelBought { cost: u32, amount: u32 },
+    PassengerSpaceBought { cost: u32 },
+    HullRepaired { cost: u32 },
+    UpgradeBought { upgrade: Upgrade, cost: u32 },
+}
+
+impl GameEvent {
+    /// The message to show the player, if the event is worth mentioning
+    pub fn message(self) -> Option<String> {
+        let message = match self {
+            Self::DamageTaken(DamageSource::Ghost) => {
+                format!("A chill runs down your spine. The ghost deals you 1 damage.")
+            }
+            Self::DamageTaken(DamageSource::Beast) => format!("The beast deals you 1 damage."),
+            Self::ItemPickedUp(junk) => format!("You pick up the {}.", junk.name()),
+            Self::PassengerBoarded(npc) => format!("The {} boards the boat.", npc.name()),
+            Self::EffectStarted { kind, turns } => kind.start_message(turns),
+            Self::EffectExpired(kind) => kind.expiry_message(),
+            Self::DayStarted(day) => format!("Day {day} begins."),
+            Self::WeatherChanged(weather) => weather.message(),
+            Self::BoatCrashed => format!("The boat crashes into a rock!"),
+            Self::HullScraped => format!("The hull scrapes along the riverbed."),
+            Self::GateOpened => format!("You hear the sound of a distant gate opening"),
+            Self::Healed => format!("Your wounds are fully healed."),
+            Self::Blinked => format!("You blink."),
+            Self::BeastDestroyed => format!("The beast is destroyed by the blast."),
+            Self::FuelBought { cost, amount } => {
+                format!("You trade {cost} junk for {amount} fuel.")
+            }
+            Self::PassengerSpaceBought { cost } => {
+                format!("You trade {cost} junk for an additional passenger space.")
+            }
+            Self::HullRepaired { cost } => {
+                format!("You trade {cost} junk to have the hull repaired.")
+            }
+            Self::UpgradeBought { upgrade, cost } => {
+                let name = upgrade.name_with_article();
+                format!("You trade {cost} junk for {name}.")
+            }
+            Self::DoorOpened | Self::DoorClosed | Self::BoatMoved | Self::Blast => return None,
+        };
+        Some(message)
+    }
+
+    pub fn sound_event(self) -> Option<SoundEvent> {
+        match self {
+            Self::DamageTaken(DamageSource::Ghost) => Some(SoundEvent::Ghost),
+            Self::DamageTaken(DamageSource::Beast) => Some(SoundEvent::BeastHit),
+            Self::ItemPickedUp(_) => Some(SoundEvent::Pickup),
+            Self::DoorOpened | Self::DoorClosed => Some(SoundEvent::Door),
+            Self::BoatMoved => Some(SoundEvent::Engine),
+            Self::Blinked => Some(SoundEvent::Blink),
+            Self::Blast => Some(SoundEvent::Blast),
+            _ => None,
+        }
+    }
+}
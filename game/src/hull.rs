@@ -0,0 +1,107 @@
+use coord_2d::Coord;
+use line_2d::coords_between_cardinal;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, sync::OnceLock};
+use vector::{Cartesian, Radians};
+
+const HULLS_JSON: &str = include_str!("../data/hulls.json");
+
+/// The shape of a boat. All coordinates are relative to the origin of the hull definition and
+/// describe the boat facing north (ie. with a heading of 0). Vertices are joined in order (and
+/// the last vertex is joined to the first) to form the outline of the boat. Everything enclosed
+/// by the outline is deck. Cabin walls are line segments drawn on the deck.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hull {
+    pub name: String,
+    pub vertices: Vec<Coord>,
+    /// Where the boat is driven from. This is the coordinate of the boat itself in the world.
+    pub control: Coord,
+    /// Where passengers sit, in the order that seats are filled
+    pub seats: Vec<Coord>,
+    pub cabin_walls: Vec<(Coord, Coord)>,
+}
+
+fn rotate(coord: Coord, heading: Radians) -> Coord {
+    Cartesian::from_coord(coord)
+        .to_radial()
+        .rotate_clockwise(heading)
+        .to_cartesian()
+        .to_coord_round_nearest()
+}
+
+impl Hull {
+    /// Every hull definition. They are parsed the first time this is called.
+    pub fn all() -> &'static [Self] {
+        static HULLS: OnceLock<Vec<Hull>> = OnceLock::new();
+        HULLS.get_or_init(|| {
+            serde_json::from_str(HULLS_JSON).expect("failed to parse hull definitions")
+        })
+    }
+
+    pub fn get(name: &str) -> Option<Self> {
+        Self::all().iter().find(|hull| hull.name == name).cloned()
+    }
+
+    pub fn num_seats(&self) -> u32 {
+        self.seats.len() as u32
+    }
+
+    /// Translates a coordinate from the hull definition so it's relative to the controls, then
+    /// rotates it to match the heading of the boat
+    fn place(&self, coord: Coord, heading: Radians) -> Coord {
+        rotate(coord - self.control, heading)
+    }
+
+    /// Coordinates of the outline of the boat relative to the controls
+    pub(crate) fn edge_coords(&self, heading: Radians) -> HashSet<Coord> {
+        let vertices = self
+            .vertices
+            .iter()
+            .map(|&v| self.place(v, heading))
+            .collect::<Vec<_>>();
+        let mut edge = HashSet::new();
+        for (i, &start) in vertices.iter().enumerate() {
+            let end = vertices[(i + 1) % vertices.len()];
+            for coord in coords_between_cardinal(start, end) {
+                edge.insert(coord);
+            }
+        }
+        edge
+    }
+
+    /// Coordinates of cabin walls relative to the controls. Walls are rasterized after rotating
+    /// so they remain connected when the boat isn't facing a cardinal direction.
+    pub(crate) fn cabin_wall_coords(&self, heading: Radians) -> HashSet<Coord> {
+        let mut walls = HashSet::new();
+        for &(start, end) in &self.cabin_walls {
+            let start = self.place(start, heading);
+            let end = self.place(end, heading);
+            for coord in coords_between_cardinal(start, end) {
+                walls.insert(coord);
+            }
+        }
+        // never wall in whoever is driving the boat
+        walls.remove(&Coord::new(0, 0));
+        walls
+    }
+
+    /// Coordinates of seats relative to the controls. Rounding can cause two seats to land on
+    /// the same coordinate when the boat is turned, in which case only the first is kept.
+    pub(crate) fn seat_coords(&self, heading: Radians) -> Vec<Coord> {
+        let mut seen = HashSet::new();
+        self.seats
+            .iter()
+            .map(|&seat| self.place(seat, heading))
+            .filter(|&coord| seen.insert(coord))
+            .collect()
+    }
+}
+
+impl Default for Hull {
+    fn default() -> Self {
+        Self::all()
+            .first()
+            .cloned()
+            .expect("there must be at least one hull")
+    }
+}
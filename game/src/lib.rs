@@ -5,23 +5,41 @@ pub use grid_search_cardinal_distance_map as distance_map;
 pub use line_2d::{self, coords_between, coords_between_cardinal};
 use rand::{seq::SliceRandom, Rng, SeedableRng};
 use rand_isaac::Isaac64Rng;
+use rational::Rational;
 pub use rgb_int::Rgb24;
 use serde::{Deserialize, Serialize};
 pub use shadowcast::Context as ShadowcastContext;
 pub use spatial_table::UpdateError;
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
     time::Duration,
 };
-use vector::{Cartesian, Radial, Radians};
-
+use vector::{Radial, Radians};
+
+mod ability;
+mod autopilot;
+mod debug;
+mod effect;
+mod event;
+mod hull;
+mod message_log;
+pub mod replay;
+mod weather;
 pub mod witness;
 mod world;
 
+pub use ability::{Ability, AbilityEffect, Targeting};
+pub use autopilot::{Autopilot, AutopilotDestination, AutopilotStop, AUTOPILOT_LOW_FUEL};
+pub use debug::{BeastBehaviour, DebugCommand, DebugError, DebugMeter, DebugSpawn, DEBUG_COMMANDS};
+pub use effect::{Effect, EffectKind, Effects, Stacking};
+pub use event::{DamageSource, GameEvent};
+pub use hull::Hull;
+pub use message_log::{LogEntry, MessageLog, MESSAGE_LOG_CAPACITY};
 pub use visible_area_detection::{
     vision_distance::Circle, CellVisibility, VisibilityGrid, World as VisibleWorld,
 };
-pub use world::data::{Boat, Layer, Location, Meter, Npc, Tile};
+pub use weather::Weather;
+pub use world::data::{Boat, Junk, Layer, Location, Meter, Npc, Tile};
 use world::{
     data::{Components, DoorState, EntityData, EntityUpdate},
     spatial::{LayerTable, Layers, SpatialTable},
@@ -37,8 +55,12 @@ pub struct Omniscient;
 #[derive(Debug, Clone, Copy)]
 pub struct Config {
     pub omniscient: Option<Omniscient>,
+    /// The game is being played by a bot rather than a person. Dialogue which has no effect on
+    /// the game (talking to townsfolk, reading graves) is skipped.
     pub demo: bool,
+    /// Debug commands are accepted
     pub debug: bool,
+    pub stats: StatsConfig,
 }
 impl Config {
     pub const OMNISCIENT: Option<Omniscient> = Some(Omniscient);
@@ -49,6 +71,31 @@ impl Default for Config {
             omniscient: None,
             demo: false,
             debug: false,
+            stats: StatsConfig::default(),
+        }
+    }
+}
+
+/// Initial values and limits of the player's stats
+#[derive(Debug, Clone, Copy)]
+pub struct StatsConfig {
+    pub health: u32,
+    pub fuel: u32,
+    pub fuel_max: u32,
+    pub day_length: u32,
+    pub junk_max: u32,
+    pub hull_integrity: u32,
+}
+
+impl Default for StatsConfig {
+    fn default() -> Self {
+        Self {
+            health: 8,
+            fuel: 400,
+            fuel_max: 800,
+            day_length: 1200,
+            junk_max: 10,
+            hull_integrity: 4,
         }
     }
 }
@@ -85,16 +132,17 @@ impl VictoryStats {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum GameOverReason {
     OutOfFuel,
     KilledByGhost,
     KilledByBeast,
     Abandoned,
     KilledBySoldier,
+    Sunk,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum MenuChoice {
     SayNothing,
     Leave,
@@ -102,6 +150,9 @@ pub enum MenuChoice {
     DontAddNpcToPassengers,
     BuyFuel { cost: u32, amount: u32 },
     BuyCrewCapacity(u32),
+    RepairHull(u32),
+    BoatUpgrades,
+    BuyUpgrade { upgrade: Upgrade, cost: u32 },
     SleepUntilMorning(u32),
     StayAtInnForever,
     AbandonQuest,
@@ -109,6 +160,118 @@ pub enum MenuChoice {
     Okay,
 }
 
+/// Permanent improvements to the boat which can be bought from innkeepers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Upgrade {
+    FuelTank,
+    Engine,
+    Lantern,
+    ReinforcedHull,
+    Houseboat,
+}
+
+/// The name of the hull in `data/hulls.json` which the boat is rebuilt with when the houseboat
+/// upgrade is bought
+const HOUSEBOAT_HULL: &str = "Houseboat";
+
+/// How much extra fuel the boat can hold after buying a larger fuel tank
+const FUEL_TANK_CAPACITY: u32 = 400;
+
+/// The chance that moving the boat consumes fuel once the engine has been upgraded
+const EFFICIENT_ENGINE_FUEL_CHANCE: Rational = Rational {
+    numerator: 2,
+    denominator: 3,
+};
+
+/// How much extra damage the boat can take after reinforcing the hull
+const REINFORCED_HULL_INTEGRITY: u32 = 2;
+
+/// The chance that moving the boat through shallows damages the hull
+const SHALLOWS_DAMAGE_CHANCE: Rational = Rational {
+    numerator: 1,
+    denominator: 4,
+};
+
+/// The river current moves the boat downstream once every this many turns while the player is
+/// aboard, whether or not they are driving
+const CURRENT_DRIFT_INTERVAL: u64 = 3;
+
+/// Beasts this many steps or fewer from the player move towards them
+const BEAST_APPROACH_DISTANCE: u32 = 12;
+
+/// Beasts this many steps or fewer from the player flee from them while the player is feared
+const BEAST_FLEE_DISTANCE: u32 = 6;
+
+/// Ghosts appear this far from the player
+const GHOST_SPAWN_DISTANCE: f64 = 10.;
+
+impl Upgrade {
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::FuelTank,
+            Self::Engine,
+            Self::Lantern,
+            Self::ReinforcedHull,
+            Self::Houseboat,
+        ]
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Self::FuelTank => format!("Larger Fuel Tank"),
+            Self::Engine => format!("Efficient Engine"),
+            Self::Lantern => format!("Lantern"),
+            Self::ReinforcedHull => format!("Reinforced Hull"),
+            Self::Houseboat => format!("Houseboat"),
+        }
+    }
+
+    /// The name in lower case with an indefinite article, for use in sentences
+    pub fn name_with_article(self) -> String {
+        match self {
+            Self::FuelTank => format!("a larger fuel tank"),
+            Self::Engine => format!("an efficient engine"),
+            Self::Lantern => format!("a lantern"),
+            Self::ReinforcedHull => format!("a reinforced hull"),
+            Self::Houseboat => format!("a houseboat"),
+        }
+    }
+
+    pub fn description(self) -> String {
+        match self {
+            Self::FuelTank => format!("Holds an extra {FUEL_TANK_CAPACITY} fuel."),
+            Self::Engine => format!("Uses a third less fuel."),
+            Self::Lantern => format!("See further at night."),
+            Self::ReinforcedHull => format!(
+                "Keeps ghosts off the boat and survives {REINFORCED_HULL_INTEGRITY} more collisions."
+            ),
+            Self::Houseboat => format!("Lengthen the boat and build a cabin on the deck."),
+        }
+    }
+
+    pub fn cost(self) -> u32 {
+        match self {
+            Self::FuelTank => 5,
+            Self::Engine => 6,
+            Self::Lantern => 3,
+            Self::ReinforcedHull => 8,
+            Self::Houseboat => 10,
+        }
+    }
+}
+
+/// Sounds made by things that happen in the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SoundEvent {
+    Engine,
+    Door,
+    Ghost,
+    BeastHit,
+    Pickup,
+    Blink,
+    Blast,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum MenuImage {
     Townsperson,
@@ -132,7 +295,7 @@ pub enum GameControlFlow {
     Aim(Npc),
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum Input {
     Walk(CardinalDirection),
     Wait,
@@ -153,12 +316,62 @@ enum MoveDirection {
 #[derive(Serialize, Deserialize, Default, Debug)]
 pub struct VisibleCellData {
     pub tiles: LayerTable<Option<Tile>>,
+    /// The specific junk lying in the cell, if any
+    pub junk: Option<Junk>,
+    /// The name of the person buried in the cell, if any
+    pub grave: Option<String>,
 }
 
 impl VisibleCellData {
     fn update(&mut self, world: &World, coord: Coord) {
         let layers = world.spatial_table.layers_at_checked(coord);
         self.tiles = layers.option_and_then(|&entity| world.components.tile.get(entity).cloned());
+        self.junk = layers
+            .item
+            .and_then(|entity| world.components.junk.get(entity).cloned());
+        self.grave = layers
+            .feature
+            .and_then(|entity| world.components.grave.get(entity))
+            .map(|victory| victory.name.clone());
+    }
+
+    /// Whether the player could walk through the cell, going by what's remembered about it
+    fn is_walkable(&self) -> bool {
+        let tiles = &self.tiles;
+        if tiles.water.is_some() && tiles.floor.is_none() {
+            return false;
+        }
+        !matches!(
+            tiles.feature,
+            Some(Tile::Wall | Tile::Rock | Tile::Tree | Tile::StairsDown | Tile::StairsUp)
+        ) && tiles.boat != Some(Tile::BoatEdge)
+    }
+
+    /// Describe the contents of the cell, starting with the most prominent thing
+    pub fn describe(&self) -> Vec<String> {
+        let tiles = &self.tiles;
+        [
+            tiles.character,
+            tiles.item,
+            tiles.feature,
+            tiles.boat,
+            tiles.floor,
+            tiles.water,
+        ]
+        .into_iter()
+        .flatten()
+        .map(|tile| match tile {
+            Tile::Junk => match self.junk {
+                Some(junk) => format!("a {}", junk.name()),
+                None => tile.description(),
+            },
+            Tile::Grave => match self.grave.as_ref() {
+                Some(name) => format!("the grave of {name}"),
+                None => tile.description(),
+            },
+            _ => tile.description(),
+        })
+        .collect()
     }
 }
 
@@ -186,6 +399,34 @@ impl VisibleWorld for World {
     }
 }
 
+/// Allows paths through cells the player has seen and remembers being walkable
+struct RememberedCanEnter<'a> {
+    visibility_grid: &'a VisibilityGrid<VisibleCellData>,
+    destinations: &'a HashSet<Coord>,
+}
+
+impl<'a> distance_map::CanEnter for RememberedCanEnter<'a> {
+    fn can_enter(&self, coord: Coord) -> bool {
+        // destinations may be unexplored, or something to bump into like a door or a person
+        if self.destinations.contains(&coord) {
+            return true;
+        }
+        match self.visibility_grid.get_visibility(coord) {
+            CellVisibility::Never => false,
+            CellVisibility::Previous(data) | CellVisibility::Current { data, .. } => {
+                data.is_walkable()
+            }
+        }
+    }
+}
+
+/// The cells covered by the boat, relative to its controls
+struct BoatShape {
+    edge: HashSet<Coord>,
+    floor: HashSet<Coord>,
+    cabin_walls: HashSet<Coord>,
+}
+
 #[derive(Serialize, Deserialize)]
 struct DungeonState {
     world_tmp: World,
@@ -215,13 +456,26 @@ pub struct Game {
     has_talked_to_npc: bool,
     night_turn_count: u32,
     messages: Vec<String>,
+    message_log: MessageLog,
     victory_stats: VictoryStats,
     passengers: Vec<Npc>,
     num_seats: u32,
     seat_rng_seed: u64,
+    hull: Hull,
+    upgrades: HashSet<Upgrade>,
+    weather: Weather,
+    events: Vec<GameEvent>,
     ai_ctx: AiCtx,
     npc_actions: HashMap<Npc, Meter>,
-    effect_timeouts: EffectTimeouts,
+    effects: Effects,
+    /// Everything on the level is visible. This is a debugging aid so it isn't saved.
+    #[serde(skip)]
+    omniscient: bool,
+    /// Cells the player has never seen which are next to walkable cells they have seen. This is
+    /// kept up to date as the player sees more of the level, so exploring doesn't scan the whole
+    /// level every step. It's rebuilt when a game is loaded rather than saved.
+    #[serde(skip)]
+    explore_frontier: HashSet<Coord>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -230,34 +484,69 @@ pub struct Stats {
     pub fuel: Meter,
     pub day: Meter,
     pub junk: Meter,
+    pub hull_integrity: Meter,
 }
 
 impl Stats {
-    fn new() -> Self {
-        let day_max = 1200;
-        let first_day_skip = 50;
+    fn new(config: &StatsConfig) -> Self {
+        let day_max = config.day_length;
+        let first_day_skip = 50.min(day_max);
         Self {
-            health: Meter::new(8, 8),
-            fuel: Meter::new(400, 800),
+            health: Meter::new(config.health, config.health),
+            fuel: Meter::new(config.fuel, config.fuel_max),
             day: Meter::new(day_max - first_day_skip, day_max),
-            junk: Meter::new(0, 10),
+            junk: Meter::new(0, config.junk_max),
+            hull_integrity: Meter::new(config.hull_integrity, config.hull_integrity),
         }
     }
 }
 
-pub enum ActionError {}
+#[derive(Debug, Clone, Copy)]
+pub enum ActionError {
+    OutOfBounds,
+    BlockedBySolidFeature,
+    BlockedByWater,
+    BlockedByCharacter,
+    BoatCannotTurn,
+    BoatCannotMove,
+    NotAtBoatControls,
+    NoPassengerInSlot(u8),
+    NoAbilityCharges(Npc),
+    TargetNotVisible,
+    TargetTooClose,
+    TargetOutOfRange,
+    DestinationNotEmpty,
+}
 
-#[derive(Serialize, Deserialize, Default)]
-pub struct EffectTimeouts {
-    pub sneak: u32,
-    pub phase: u32,
-    pub fear: u32,
-    pub telescope: u32,
+impl ActionError {
+    pub fn message(self) -> String {
+        match self {
+            Self::OutOfBounds => format!("You can't go that way."),
+            Self::BlockedBySolidFeature => format!("Something solid blocks your way."),
+            Self::BlockedByWater => format!("The water is too deep to wade through."),
+            Self::BlockedByCharacter => format!("Someone is in your way."),
+            Self::BoatCannotTurn => format!("There isn't enough room to turn the boat."),
+            Self::BoatCannotMove => format!("The boat is blocked."),
+            Self::NotAtBoatControls => {
+                format!("You must stand at the boat's controls to drive it.")
+            }
+            Self::NoPassengerInSlot(index) => {
+                format!("There is no passenger in position {}.", index)
+            }
+            Self::NoAbilityCharges(npc) => {
+                format!("{} has no remaining actions today.", npc.name())
+            }
+            Self::TargetNotVisible => format!("Can't see the target."),
+            Self::TargetTooClose => format!("Within minimum safe distance. Refusing to fire."),
+            Self::TargetOutOfRange => format!("The target is out of range."),
+            Self::DestinationNotEmpty => format!("Destination is not empty!"),
+        }
+    }
 }
 
 impl Game {
-    pub fn new<R: Rng>(_config: &Config, victories: Vec<Victory>, base_rng: &mut R) -> Self {
-        let mut rng = Isaac64Rng::seed_from_u64(base_rng.gen());
+    pub fn new(config: &Config, victories: Vec<Victory>, seed: u64) -> Self {
+        let mut rng = Isaac64Rng::seed_from_u64(seed);
         let Terrain {
             world,
             player_entity,
@@ -275,33 +564,26 @@ impl Game {
             driving: false,
             dungeons,
             dungeon_state: None,
-            stats: Stats::new(),
+            stats: Stats::new(&config.stats),
             has_been_on_boat: false,
             has_crossed_threshold: false,
             has_talked_to_npc: false,
             night_turn_count: 0,
             messages: Vec::new(),
+            message_log: MessageLog::default(),
             victory_stats: VictoryStats::new(),
             passengers: vec![],
             num_seats: 1,
+            hull: Hull::default(),
+            upgrades: HashSet::new(),
+            weather: Weather::Clear,
+            events: Vec::new(),
             ai_ctx: Default::default(),
             npc_actions: Default::default(),
-            effect_timeouts: Default::default(),
+            effects: Default::default(),
+            omniscient: config.omniscient.is_some(),
+            explore_frontier: HashSet::new(),
         };
-        let debug = false;
-        if debug {
-            game.has_crossed_threshold = true;
-            game.has_talked_to_npc = true;
-            game.has_been_on_boat = true;
-            game.add_npc_to_passengers_(Npc::Soldier);
-            game.add_npc_to_passengers_(Npc::Physicist);
-            game.add_npc_to_passengers_(Npc::Beast);
-            game.add_npc_to_passengers_(Npc::Ghost);
-            game.add_npc_to_passengers_(Npc::Surgeon);
-            game.add_npc_to_passengers_(Npc::Thief);
-            game.add_npc_to_passengers_(Npc::Surveyor);
-            game.num_seats = 8;
-        }
         let (boat_entity, boat) = game.world.components.boat.iter().next().unwrap();
         let boat_coord = game.world.spatial_table.coord_of(boat_entity).unwrap();
         if !game.try_rasterize_boat(boat_entity, boat.clone(), boat_coord) {
@@ -314,20 +596,20 @@ impl Game {
         game
     }
 
-    pub fn effect_timeouts(&self) -> &EffectTimeouts {
-        &self.effect_timeouts
-    }
-    fn is_sneak(&self) -> bool {
-        self.effect_timeouts.sneak > 0
+    pub fn effects(&self) -> &Effects {
+        &self.effects
     }
-    fn is_fear(&self) -> bool {
-        self.effect_timeouts.fear > 0
+    pub fn is_effect_active(&self, kind: EffectKind) -> bool {
+        self.effects.is_active(kind)
     }
-    fn is_phase(&self) -> bool {
-        self.effect_timeouts.phase > 0
+    fn start_effect(&mut self, kind: EffectKind, turns: u32) {
+        // the turn spent starting the effect counts it down immediately
+        self.effects.apply(kind, turns + 1);
+        self.event(GameEvent::EffectStarted { kind, turns });
     }
-    fn is_telescope(&self) -> bool {
-        self.effect_timeouts.telescope > 0
+
+    pub fn world_size(&self) -> Size {
+        self.world.size()
     }
 
     pub fn current_day(&self) -> u32 {
@@ -340,6 +622,8 @@ impl Game {
         self.stats.health.fill();
         self.night_turn_count = 0;
         self.messages.clear();
+        self.event(GameEvent::DayStarted(self.current_day()));
+        self.change_weather();
         let ghosts = self.world.components.ghost.entities().collect::<Vec<_>>();
         for entity in ghosts {
             self.world.components.remove_entity(entity);
@@ -350,10 +634,26 @@ impl Game {
         }
     }
 
+    pub fn weather(&self) -> Weather {
+        self.weather
+    }
+
+    fn change_weather(&mut self) {
+        let weather = self.weather.next(&mut self.rng);
+        if weather != self.weather {
+            self.weather = weather;
+            self.event(GameEvent::WeatherChanged(weather));
+        }
+    }
+
     pub fn num_seats(&self) -> u32 {
         self.num_seats
     }
 
+    pub fn hull(&self) -> &Hull {
+        &self.hull
+    }
+
     pub fn passengers(&self) -> &[Npc] {
         &self.passengers
     }
@@ -366,9 +666,19 @@ impl Game {
         &self.victory_stats
     }
 
+    /// The number of entities in the current level with each kind of tile, for tools that
+    /// inspect saved games
+    pub fn tile_counts(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for (_, tile) in self.world.components.tile.iter() {
+            *counts.entry(format!("{:?}", tile)).or_insert(0) += 1;
+        }
+        counts
+    }
+
     pub fn spawn_ghost(&mut self) {
         let angle = Radians(self.rng.gen::<f64>() * (2.0 * std::f64::consts::PI));
-        let length = 10.;
+        let length = GHOST_SPAWN_DISTANCE;
         let coord = Radial { length, angle }
             .to_cartesian()
             .to_coord_round_nearest()
@@ -383,36 +693,26 @@ impl Game {
     }
 
     fn tick_effects(&mut self) {
-        if self.effect_timeouts.phase == 1 {
-            self.messages
-                .push(format!("You can no longer walk through walls."));
+        for kind in self.effects.tick() {
+            self.event(GameEvent::EffectExpired(kind));
         }
-        if self.effect_timeouts.fear == 1 {
-            self.messages
-                .push(format!("Beasts no longer flee from you."));
-        }
-        if self.effect_timeouts.sneak == 1 {
-            self.messages
-                .push(format!("You are no longer undetectable by beasts."));
-        }
-        if self.effect_timeouts.telescope == 1 {
-            self.messages
-                .push(format!("You give the surveyor back their telescope."));
-        }
-        self.effect_timeouts.phase = self.effect_timeouts.phase.saturating_sub(1);
-        self.effect_timeouts.fear = self.effect_timeouts.fear.saturating_sub(1);
-        self.effect_timeouts.sneak = self.effect_timeouts.sneak.saturating_sub(1);
-        self.effect_timeouts.telescope = self.effect_timeouts.telescope.saturating_sub(1);
     }
 
     pub fn pass_time(&mut self) {
         self.tick_effects();
         self.victory_stats.num_turns += 1;
         if self.has_been_on_boat {
+            let was_day = !self.stats.day.is_empty();
             self.stats.day.decrease(1);
+            if was_day && self.stats.day.is_empty() {
+                self.change_weather();
+            }
+        }
+        if self.victory_stats.num_turns % CURRENT_DRIFT_INTERVAL == 0 {
+            self.drift_boat();
         }
         if self.is_player_outside_at_night() {
-            if self.night_turn_count % 20 == 0 {
+            if self.night_turn_count % self.weather.ghost_spawn_interval() == 0 {
                 self.spawn_ghost();
             }
             self.night_turn_count += 1;
@@ -426,6 +726,47 @@ impl Game {
         }
     }
 
+    /// The coordinate of the boat controls, or `None` while the boat is in a different level
+    pub fn boat_coord(&self) -> Option<Coord> {
+        self.boat().map(|(_, boat_coord)| boat_coord)
+    }
+
+    /// The boat and the coordinate of its controls, or `None` while in a different level
+    pub(crate) fn boat(&self) -> Option<(&Boat, Coord)> {
+        let (boat_entity, boat) = self.world.components.boat.iter().next()?;
+        let boat_coord = self.world.spatial_table.coord_of(boat_entity)?;
+        Some((boat, boat_coord))
+    }
+
+    /// The direction of the current flowing under the boat's controls, if any
+    fn boat_current(&self) -> Option<Coord> {
+        let boat_coord = self.boat_coord()?;
+        let water = self.world.spatial_table.layers_at(boat_coord)?.water?;
+        self.world.components.current.get(water).cloned()
+    }
+
+    /// A boat the player has left is moored, so it's still where they left it when they return
+    fn drift_boat(&mut self) {
+        if !self.driving && !self.is_player_on_boat() {
+            return;
+        }
+        if let Some(flow) = self.boat_current() {
+            // a player driving the boat is moved with it when it's rasterized
+            let carry_player = !self.driving;
+            let (boat_entity, boat) = self.world.components.boat.iter().next().unwrap();
+            let boat_coord = self.world.spatial_table.coord_of(boat_entity).unwrap();
+            // If the current pushes the boat into something then it stays where it is
+            if self.try_rasterize_boat(boat_entity, boat.clone(), boat_coord + flow) && carry_player
+            {
+                let player_coord = self.player_coord();
+                let _ = self
+                    .world
+                    .spatial_table
+                    .update_coord(self.player_entity, player_coord + flow);
+            }
+        }
+    }
+
     pub fn spend_fuel(&mut self) {
         self.stats.fuel.decrease(1)
     }
@@ -438,6 +779,28 @@ impl Game {
         &self.messages
     }
 
+    /// Everything that happened during the most recent action
+    pub fn events(&self) -> &[GameEvent] {
+        &self.events
+    }
+
+    /// Messages from previous days as well as the current one, with the time they were shown
+    pub fn message_log(&self) -> &MessageLog {
+        &self.message_log
+    }
+
+    fn event(&mut self, event: GameEvent) {
+        if let Some(message) = event.message() {
+            self.message_log.push(LogEntry {
+                day: self.current_day(),
+                turn: self.victory_stats.num_turns,
+                message: message.clone(),
+            });
+            self.messages.push(message);
+        }
+        self.events.push(event);
+    }
+
     pub fn is_coord_inside(&self, coord: Coord) -> bool {
         if let Layers {
             floor: Some(floor), ..
@@ -480,6 +843,21 @@ impl Game {
         &self.stats
     }
 
+    pub fn has_upgrade(&self, upgrade: Upgrade) -> bool {
+        self.upgrades.contains(&upgrade)
+    }
+
+    fn is_coord_part_of_boat(&self, coord: Coord) -> bool {
+        if let Some(&Layers { floor, feature, .. }) = self.world.spatial_table.layers_at(coord) {
+            [floor, feature]
+                .into_iter()
+                .flatten()
+                .any(|entity| self.world.components.part_of_boat.contains(entity))
+        } else {
+            false
+        }
+    }
+
     pub fn has_been_on_boat(&self) -> bool {
         self.has_been_on_boat
     }
@@ -498,15 +876,16 @@ impl Game {
         let update_fn = |data: &mut VisibleCellData, coord| {
             data.update(&self.world, coord);
         };
-        let distance = if self.stats.day.is_empty() {
-            Circle::new_squared(150)
-        } else {
-            if self.is_telescope() {
-                Circle::new_squared(3000)
-            } else {
-                Circle::new_squared(500)
-            }
-        };
+        if self.omniscient {
+            self.visibility_grid.update_omniscient_custom(
+                Rgb24::new_grey(255),
+                &self.world,
+                update_fn,
+            );
+            self.rebuild_explore_frontier();
+            return;
+        }
+        let distance = Circle::new_squared(self.vision_distance_squared());
         self.visibility_grid.update_custom(
             Rgb24::new_grey(255),
             &self.world,
@@ -514,110 +893,123 @@ impl Game {
             self.player_coord(),
             update_fn,
         );
+        // only cells the player can see, and their neighbours, can have joined or left the
+        // frontier
+        let radius = (self.vision_distance_squared() as f64).sqrt().ceil() as i32 + 1;
+        self.update_explore_frontier(self.player_coord(), radius);
     }
 
-    fn try_rasterize_boat(&mut self, boat_entity: Entity, boat: Boat, boat_coord: Coord) -> bool {
-        //        #
-        //      #####
-        //     ##   ##
-        //    ##     ##
-        //    #   @   #
-        //    #       #
-        //    # ###+# #
-        //    # #   # #
-        //    # #   # #
-        //    # #+### #
-        //    #       #
-        //    ##     ##
-        //     #######
-        let mut local_rng = Isaac64Rng::seed_from_u64(self.seat_rng_seed);
-        let boat_width1 = 3;
-        let boat_width2 = 3;
-        let boat_width3 = 2;
-        let boat_length1 = 4;
-        let boat_length2 = 6;
-        let boat_length3 = 1;
-        let boat_length4 = 4;
-        let vertices_int = vec![
-            Coord::new(0, -boat_length4),            // 0
-            Coord::new(boat_width1, -boat_length3),  // 1
-            Coord::new(boat_width2, boat_length1),   // 2
-            Coord::new(boat_width3, boat_length2),   // 3
-            Coord::new(0, boat_length2),             // 4
-            Coord::new(-boat_width3, boat_length2),  // 5
-            Coord::new(-boat_width2, boat_length1),  // 6
-            Coord::new(-boat_width1, -boat_length3), // 7
-        ];
-        let vertices_rotated = vertices_int
-            .into_iter()
-            .map(|v| {
-                Cartesian::from_coord(v)
-                    .to_radial()
-                    .rotate_clockwise(boat.heading())
-                    .to_cartesian()
-                    .to_coord_round_nearest()
-            })
-            .collect::<Vec<_>>();
-        let pairs = {
-            let vs = &vertices_rotated;
-            vec![
-                (vs[0], vs[1]),
-                (vs[1], vs[2]),
-                (vs[2], vs[3]),
-                (vs[3], vs[4]),
-                (vs[0], vs[7]),
-                (vs[7], vs[6]),
-                (vs[6], vs[5]),
-                (vs[5], vs[4]),
-            ]
-        };
-        let mut boat_edge = HashSet::new();
-        for (start, end) in pairs {
-            for coord in coords_between_cardinal(start, end) {
-                boat_edge.insert(coord);
+    /// The square of how far the player can see
+    pub fn vision_distance_squared(&self) -> u32 {
+        let distance_squared = if self.stats.day.is_empty() {
+            if self.has_upgrade(Upgrade::Lantern) {
+                400
+            } else {
+                150
             }
+        } else {
+            if self.is_effect_active(EffectKind::Telescope) {
+                3000
+            } else {
+                500
+            }
+        };
+        if self.is_in_dungeon() {
+            distance_squared
+        } else {
+            self.weather.vision_distance_squared(distance_squared)
         }
+    }
 
-        let mut boat_floor = HashSet::new();
+    /// The cells covered by the boat at a heading, relative to its controls
+    fn boat_shape(&self, boat_heading: Radians) -> BoatShape {
+        let edge = self.hull.edge_coords(boat_heading);
+        let mut floor = HashSet::new();
         let mut to_visit = VecDeque::new();
-        boat_floor.insert(Coord::new(0, 0));
+        floor.insert(Coord::new(0, 0));
         to_visit.push_back(Coord::new(0, 0));
         while let Some(coord) = to_visit.pop_front() {
             for d in CardinalDirection::all() {
                 let nei_coord = coord + d.coord();
-                if !boat_edge.contains(&nei_coord) {
-                    if boat_floor.insert(nei_coord) {
+                if !edge.contains(&nei_coord) {
+                    if floor.insert(nei_coord) {
                         to_visit.push_back(nei_coord);
                     }
                 }
             }
         }
+        let cabin_walls = self
+            .hull
+            .cabin_wall_coords(boat_heading)
+            .into_iter()
+            .filter(|coord| floor.contains(coord))
+            .collect::<HashSet<_>>();
+        BoatShape {
+            edge,
+            floor,
+            cabin_walls,
+        }
+    }
 
-        for &coord in &boat_floor {
-            if let Some(floor_entity) = self
+    /// Returns true if nothing that isn't part of the boat is in the way of the boat's floor,
+    /// cabin walls or edge
+    fn is_boat_shape_clear(&self, shape: &BoatShape, boat_coord: Coord) -> bool {
+        let is_part_of_boat = |entity| self.world.components.part_of_boat.contains(entity);
+        for &coord in &shape.floor {
+            let layers = self
                 .world
                 .spatial_table
-                .layers_at_checked(coord + boat_coord)
-                .floor
-            {
-                if !self.world.components.part_of_boat.contains(floor_entity) {
+                .layers_at_checked(coord + boat_coord);
+            if let Some(floor_entity) = layers.floor {
+                if !is_part_of_boat(floor_entity) {
                     return false;
                 }
             }
+            if shape.cabin_walls.contains(&coord) {
+                if let Some(feature_entity) = layers.feature {
+                    if !is_part_of_boat(feature_entity) {
+                        return false;
+                    }
+                }
+            }
         }
-
-        let mut edges_to_turn_into_floors = HashSet::new();
-        for &coord in &boat_edge {
+        for &coord in &shape.edge {
             if let Some(feature_entity) = self
                 .world
                 .spatial_table
                 .layers_at_checked(coord + boat_coord)
                 .feature
             {
-                if !self.world.components.part_of_boat.contains(feature_entity) {
+                if !is_part_of_boat(feature_entity) {
                     return false;
                 }
             }
+        }
+        true
+    }
+
+    /// Returns true if the boat could be moved to a coord without crashing or being blocked.
+    /// Unlike `try_rasterize_boat` this doesn't change the world.
+    pub(crate) fn can_boat_occupy(&self, boat: &Boat, boat_coord: Coord) -> bool {
+        !self.would_boat_hit_hazard(boat, boat_coord)
+            && self.is_boat_shape_clear(&self.boat_shape(boat.heading()), boat_coord)
+    }
+
+    fn try_rasterize_boat(&mut self, boat_entity: Entity, boat: Boat, boat_coord: Coord) -> bool {
+        let mut local_rng = Isaac64Rng::seed_from_u64(self.seat_rng_seed);
+        let boat_heading = boat.heading();
+        let shape = self.boat_shape(boat_heading);
+        if !self.is_boat_shape_clear(&shape, boat_coord) {
+            return false;
+        }
+        let BoatShape {
+            edge: mut boat_edge,
+            floor: mut boat_floor,
+            cabin_walls,
+        } = shape;
+
+        let mut edges_to_turn_into_floors = HashSet::new();
+        for &coord in &boat_edge {
             if let Some(floor_entity) = self
                 .world
                 .spatial_table
@@ -630,8 +1022,6 @@ impl Game {
             }
         }
 
-        let boat_heading = boat.heading();
-
         let mut to_delete = Vec::new();
         for entity in self.world.components.part_of_boat.entities() {
             to_delete.push(entity);
@@ -651,6 +1041,9 @@ impl Game {
         for &coord in &boat_floor {
             self.world.spawn_boat_floor(coord + boat_coord);
         }
+        for &coord in &cabin_walls {
+            self.world.spawn_boat_wall(coord + boat_coord);
+        }
         self.world.spawn_boat_controls(boat_coord);
         self.world.components.boat.insert(boat_entity, boat);
         let _ = self
@@ -664,84 +1057,163 @@ impl Game {
                 .update_coord(self.player_entity, boat_coord);
         }
         if !self.stats.day.is_empty() {
-            let mut boat_floor = boat_floor
+            let player_coord = self.player_coord();
+            let is_free = |c: &Coord| !cabin_walls.contains(c) && *c + boat_coord != player_coord;
+            let seats = self
+                .hull
+                .seat_coords(boat_heading)
                 .into_iter()
-                .filter(|c| *c + boat_coord != self.player_coord())
+                .filter(|c| boat_floor.contains(c) && is_free(c))
                 .collect::<Vec<_>>();
-            boat_floor.sort();
-            boat_floor.shuffle(&mut local_rng);
+            // If some seats can't be used, passengers stand wherever there is room
+            let mut standing = boat_floor
+                .iter()
+                .cloned()
+                .filter(|c| is_free(c) && !seats.contains(c))
+                .collect::<Vec<_>>();
+            standing.sort();
+            standing.shuffle(&mut local_rng);
+            let mut spots = seats.into_iter().chain(standing.into_iter().rev());
             for &npc in &self.passengers {
-                if let Some(coord) = boat_floor.pop() {
+                if let Some(coord) = spots.next() {
                     let e = self.world.spawn_npc(coord + boat_coord, npc);
                     self.world.components.part_of_boat.insert(e, ());
                     let _ = self.world.spatial_table.update_layer(e, Layer::Feature);
                 }
             }
         }
-        if false {
-            // the cabin is tricky to get right when the boat is not facing a cardinal direction
-            let unit = Cartesian { x: 1., y: 0. }
-                .to_radial()
-                .rotate_clockwise(boat_heading)
-                .to_cartesian()
-                .to_coord_round_nearest();
-            let is_cardinal = unit.x * unit.y == 0;
-
-            if is_cardinal {
-                let cabin_top_left = Cartesian { x: -2., y: 3. }
-                    .to_radial()
-                    .rotate_clockwise(boat_heading)
-                    .to_cartesian()
-                    .to_coord_round_nearest();
-                let cabin_top_right = Cartesian { x: 0., y: 3. }
-                    .to_radial()
-                    .rotate_clockwise(boat_heading)
-                    .to_cartesian()
-                    .to_coord_round_nearest();
-                let cabin_bottom_left = Cartesian { x: -2., y: 7. }
-                    .to_radial()
-                    .rotate_clockwise(boat_heading)
-                    .to_cartesian()
-                    .to_coord_round_nearest();
-                for coord in coords_between_cardinal(cabin_top_left, cabin_top_right) {
-                    self.world.spawn_boat_wall(coord + boat_coord);
-                }
-                for coord in coords_between_cardinal(cabin_top_left, cabin_bottom_left).skip(1) {
-                    self.world.spawn_boat_wall(coord + boat_coord);
-                }
-                let cabin_top_right = Cartesian { x: 2., y: 3. }
-                    .to_radial()
-                    .rotate_clockwise(boat_heading)
-                    .to_cartesian()
-                    .to_coord_round_nearest();
-                let cabin_bottom_right = Cartesian { x: 2., y: 7. }
-                    .to_radial()
-                    .rotate_clockwise(boat_heading)
-                    .to_cartesian()
-                    .to_coord_round_nearest();
-                let cabin_bottom_left = Cartesian { x: 0., y: 7. }
-                    .to_radial()
-                    .rotate_clockwise(boat_heading)
-                    .to_cartesian()
-                    .to_coord_round_nearest();
-                for coord in coords_between_cardinal(cabin_top_right, cabin_bottom_right) {
-                    self.world.spawn_boat_wall(coord + boat_coord);
+        true
+    }
+
+    pub fn cell_visibility_at_coord(&self, coord: Coord) -> CellVisibility<&VisibleCellData> {
+        self.visibility_grid.get_visibility(coord)
+    }
+
+    /// The direction the player should walk to get one step closer to a coord, travelling only
+    /// through cells they have seen. Returns `None` if there's no known path or the player is
+    /// already there.
+    pub fn direction_towards(&self, destination: Coord) -> Option<CardinalDirection> {
+        if destination == self.player_coord() || !destination.is_valid(self.world.size()) {
+            return None;
+        }
+        self.direction_towards_nearest(&[destination].into_iter().collect())
+    }
+
+    /// The direction the player should walk to get one step closer to the nearest cell they
+    /// haven't seen, travelling only through cells they have seen. Returns `None` if there's
+    /// nothing left to explore that the player knows how to reach.
+    pub fn direction_to_explore(&self) -> Option<CardinalDirection> {
+        if self.explore_frontier.is_empty() {
+            return None;
+        }
+        self.direction_towards_nearest(&self.explore_frontier)
+    }
+
+    /// Returns true if the player has never seen the cell but has seen a walkable cell next to it
+    fn is_explore_frontier(&self, coord: Coord) -> bool {
+        let is_walkable = |coord| match self.cell_visibility_at_coord(coord) {
+            CellVisibility::Never => false,
+            CellVisibility::Previous(data) | CellVisibility::Current { data, .. } => {
+                data.is_walkable()
+            }
+        };
+        matches!(self.cell_visibility_at_coord(coord), CellVisibility::Never)
+            && CardinalDirection::all().any(|d| is_walkable(coord + d.coord()))
+    }
+
+    /// Recheck whether each cell within `radius` (horizontally and vertically) of `centre` is on
+    /// the explore frontier
+    fn update_explore_frontier(&mut self, centre: Coord, radius: i32) {
+        let size = self.world.size();
+        for y in (centre.y - radius)..=(centre.y + radius) {
+            for x in (centre.x - radius)..=(centre.x + radius) {
+                let coord = Coord::new(x, y);
+                if !coord.is_valid(size) {
+                    continue;
                 }
-                for coord in coords_between_cardinal(cabin_bottom_right, cabin_bottom_left).skip(1)
-                {
-                    self.world.spawn_boat_wall(coord + boat_coord);
+                if self.is_explore_frontier(coord) {
+                    self.explore_frontier.insert(coord);
+                } else {
+                    self.explore_frontier.remove(&coord);
                 }
             }
         }
+    }
 
-        true
+    fn rebuild_explore_frontier(&mut self) {
+        self.explore_frontier = self
+            .world
+            .size()
+            .coord_iter_row_major()
+            .filter(|&coord| self.is_explore_frontier(coord))
+            .collect();
+    }
+
+    /// Restore the state which isn't saved when a game is loaded. Omniscience is switched on
+    /// again if `Config::omniscient` is set.
+    pub(crate) fn resume(&mut self, config: &Config) {
+        self.rebuild_explore_frontier();
+        if config.omniscient.is_some() && !self.omniscient {
+            self.omniscient = true;
+            self.update_visibility();
+        }
     }
 
-    pub fn cell_visibility_at_coord(&self, coord: Coord) -> CellVisibility<&VisibleCellData> {
-        self.visibility_grid.get_visibility(coord)
+    fn direction_towards_nearest(
+        &self,
+        destinations: &HashSet<Coord>,
+    ) -> Option<CardinalDirection> {
+        let can_enter = RememberedCanEnter {
+            visibility_grid: &self.visibility_grid,
+            destinations,
+        };
+        let mut ctx = distance_map::PopulateContext::default();
+        let mut distance_map = distance_map::DistanceMap::new(self.world.size());
+        for &destination in destinations {
+            ctx.add(destination);
+        }
+        // far enough to cross the whole world
+        ctx.populate_approach(&can_enter, 1000, &mut distance_map);
+        distance_map.direction_to_best_neighbour(self.player_coord())
+    }
+
+    /// True if a beast or ghost is in view
+    pub fn is_enemy_visible(&self) -> bool {
+        let components = &self.world.components;
+        components
+            .beast
+            .entities()
+            .chain(components.ghost.entities())
+            .filter_map(|entity| self.world.spatial_table.coord_of(entity))
+            .any(|coord| {
+                matches!(
+                    self.cell_visibility_at_coord(coord),
+                    CellVisibility::Current { .. }
+                )
+            })
     }
 
-    fn rotate_boat(&mut self, rotate_direction: RotateDirection) -> Option<GameControlFlow> {
+    /// Describe what the player can see, or remembers seeing, at a coord
+    pub fn examine(&self, coord: Coord) -> String {
+        let list = |data: &VisibleCellData| {
+            let mut descriptions = data.describe();
+            match descriptions.pop() {
+                None => format!("nothing"),
+                Some(last) if descriptions.is_empty() => last,
+                Some(last) => format!("{} and {}", descriptions.join(", "), last),
+            }
+        };
+        match self.cell_visibility_at_coord(coord) {
+            CellVisibility::Never => format!("You haven't seen this place."),
+            CellVisibility::Previous(data) => format!("You remember seeing {}.", list(data)),
+            CellVisibility::Current { data, .. } => format!("You see {}.", list(data)),
+        }
+    }
+
+    fn rotate_boat(
+        &mut self,
+        rotate_direction: RotateDirection,
+    ) -> Result<Option<GameControlFlow>, ActionError> {
         let (boat_entity, boat) = self.world.components.boat.iter().next().unwrap();
         let step_radians = std::f64::consts::FRAC_PI_4;
         let delta_radians = match rotate_direction {
@@ -750,22 +1222,100 @@ impl Game {
         };
         let boat_next = boat.add_heading(Radians(delta_radians));
         let boat_coord = self.world.spatial_table.coord_of(boat_entity).unwrap();
-        self.try_rasterize_boat(boat_entity, boat_next, boat_coord);
+        if self.would_boat_hit_hazard(&boat_next, boat_coord) {
+            return Ok(self.crash_boat());
+        }
+        if !self.try_rasterize_boat(boat_entity, boat_next, boat_coord) {
+            return Err(ActionError::BoatCannotTurn);
+        }
+        self.event(GameEvent::BoatMoved);
         self.pass_time();
-        None
+        Ok(None)
     }
 
-    fn move_boat(&mut self, move_direction: MoveDirection) -> Option<GameControlFlow> {
+    fn move_boat(
+        &mut self,
+        move_direction: MoveDirection,
+    ) -> Result<Option<GameControlFlow>, ActionError> {
         let (boat_entity, boat) = self.world.components.boat.iter().next().unwrap();
         let boat_coord = self.world.spatial_table.coord_of(boat_entity).unwrap();
         let (boat_next, delta) = match move_direction {
             MoveDirection::Forward => boat.step(),
             MoveDirection::Backward => boat.step_backwards(),
         };
-        self.try_rasterize_boat(boat_entity, boat_next, boat_coord + delta);
+        if self.would_boat_hit_hazard(&boat_next, boat_coord + delta) {
+            return Ok(self.crash_boat());
+        }
+        let current = self.boat_current();
+        if !self.try_rasterize_boat(boat_entity, boat_next, boat_coord + delta) {
+            return Err(ActionError::BoatCannotMove);
+        }
+        self.event(GameEvent::BoatMoved);
+        if let Some(flow) = current {
+            if flow.x * delta.x + flow.y * delta.y < 0 {
+                // fighting the current takes extra fuel
+                self.spend_fuel();
+            }
+        }
         self.pass_time();
-        self.spend_fuel();
-        None
+        if !self.has_upgrade(Upgrade::Engine) || EFFICIENT_ENGINE_FUEL_CHANCE.roll(&mut self.rng) {
+            self.spend_fuel();
+        }
+        if self.is_boat_over_shallows() && SHALLOWS_DAMAGE_CHANCE.roll(&mut self.rng) {
+            return Ok(self.damage_hull(GameEvent::HullScraped));
+        }
+        Ok(None)
+    }
+
+    /// Returns true if the outline of the boat would overlap a hazard such as a rock
+    fn would_boat_hit_hazard(&self, boat: &Boat, boat_coord: Coord) -> bool {
+        self.hull
+            .edge_coords(boat.heading())
+            .into_iter()
+            .any(|coord| {
+                if let Some(&Layers {
+                    feature: Some(feature),
+                    ..
+                }) = self.world.spatial_table.layers_at(coord + boat_coord)
+                {
+                    self.world.components.hazard.contains(feature)
+                } else {
+                    false
+                }
+            })
+    }
+
+    fn is_boat_over_shallows(&self) -> bool {
+        let (boat_entity, boat) = self.world.components.boat.iter().next().unwrap();
+        let boat_coord = self.world.spatial_table.coord_of(boat_entity).unwrap();
+        self.hull
+            .edge_coords(boat.heading())
+            .into_iter()
+            .any(|coord| {
+                if let Some(&Layers {
+                    water: Some(water), ..
+                }) = self.world.spatial_table.layers_at(coord + boat_coord)
+                {
+                    self.world.components.shallows.contains(water)
+                } else {
+                    false
+                }
+            })
+    }
+
+    fn crash_boat(&mut self) -> Option<GameControlFlow> {
+        self.pass_time();
+        self.damage_hull(GameEvent::BoatCrashed)
+    }
+
+    fn damage_hull(&mut self, event: GameEvent) -> Option<GameControlFlow> {
+        self.stats.hull_integrity.decrease(1);
+        self.event(event);
+        if self.stats.hull_integrity.is_empty() {
+            Some(GameControlFlow::GameOver(GameOverReason::Sunk))
+        } else {
+            None
+        }
     }
 
     // Returns the coordinate of the player character
@@ -777,6 +1327,7 @@ impl Game {
     }
 
     fn open_door(&mut self, entity: Entity) {
+        self.event(GameEvent::DoorOpened);
         self.world.components.apply_entity_update(
             entity,
             entity_update! {
@@ -806,6 +1357,7 @@ impl Game {
     }
 
     fn close_door(&mut self, entity: Entity) {
+        self.event(GameEvent::DoorClosed);
         self.world.components.insert_entity_data(
             entity,
             entity_data! {
@@ -817,11 +1369,15 @@ impl Game {
         );
     }
 
-    fn player_walk(&mut self, direction: CardinalDirection) -> Option<GameControlFlow> {
+    fn player_walk(
+        &mut self,
+        direction: CardinalDirection,
+        config: &Config,
+    ) -> Result<Option<GameControlFlow>, ActionError> {
         let player_coord = self.player_coord();
         let new_player_coord = player_coord + direction.coord();
         if !new_player_coord.is_valid(self.world.size()) {
-            return None;
+            return Err(ActionError::OutOfBounds);
         }
         let layers = self
             .world
@@ -834,11 +1390,11 @@ impl Game {
         }) = layers
         {
             if let Some(state) = self.world.components.button.get_mut(feature_entity) {
-                if !*state {
-                    self.messages
-                        .push(format!("You hear the sound of a distant gate opening"));
-                }
+                let was_pressed = *state;
                 *state = true;
+                if !was_pressed {
+                    self.event(GameEvent::GateOpened);
+                }
                 self.world
                     .components
                     .tile
@@ -849,15 +1405,15 @@ impl Game {
                     world.components.remove_entity(e);
                     world.spatial_table.remove(e);
                 }
-                return None;
+                return Ok(None);
             }
-            if !self.is_phase() {
+            if !self.is_effect_active(EffectKind::Phase) {
                 // If the player bumps into a door, open the door
                 if let Some(DoorState::Closed) =
                     self.world.components.door_state.get(feature_entity)
                 {
                     self.open_door(feature_entity);
-                    return None;
+                    return Ok(None);
                 }
                 // Don't let the player walk through solid entities
                 if self.world.components.solid.contains(feature_entity) {
@@ -865,8 +1421,9 @@ impl Game {
                         self.open_door_entity_adjacent_to_coord(player_coord)
                     {
                         self.close_door(open_door_entity);
+                        return Ok(None);
                     }
-                    return None;
+                    return Err(ActionError::BlockedBySolidFeature);
                 }
             }
             {
@@ -879,30 +1436,30 @@ impl Game {
                     {
                         if self.world.components.ghost.contains(entity) {
                             self.take_damage();
-                            self.ghost_message();
+                            self.event(GameEvent::DamageTaken(DamageSource::Ghost));
                             if self.stats.health.is_empty() {
-                                return Some(GameControlFlow::GameOver(
+                                return Ok(Some(GameControlFlow::GameOver(
                                     GameOverReason::KilledByGhost,
-                                ));
+                                )));
                             }
                         }
                         if self.world.components.beast.contains(entity) {
                             self.take_damage();
-                            self.beast_message();
+                            self.event(GameEvent::DamageTaken(DamageSource::Beast));
                             if self.stats.health.is_empty() {
-                                return Some(GameControlFlow::GameOver(
+                                return Ok(Some(GameControlFlow::GameOver(
                                     GameOverReason::KilledByBeast,
-                                ));
+                                )));
                             }
                         }
                     }
                     self.enter_dungeon(dungeon_index);
-                    return None;
+                    return Ok(None);
                 }
             }
             if self.world.components.stairs_up.contains(feature_entity) {
                 self.exit_dungeon();
-                return None;
+                return Ok(None);
             }
         }
         if let Some(Layers {
@@ -912,7 +1469,7 @@ impl Game {
             ..
         }) = layers
         {
-            return None;
+            return Err(ActionError::BlockedByWater);
         }
         if let Some(Layers { boat: Some(_), .. }) = layers {
             self.has_been_on_boat = true;
@@ -926,11 +1483,15 @@ impl Game {
                 self.has_crossed_threshold = true;
             }
             if let Some(victory) = self.world.components.grave.get(feature) {
-                return Some(GameControlFlow::Menu(Menu {
+                if config.demo {
+                    // the bot reads the grave without stopping, like talking to townsfolk
+                    return Ok(None);
+                }
+                return Ok(Some(GameControlFlow::Menu(Menu {
                     choices: vec![MenuChoice::Leave],
                     text: victory.text(),
                     image: MenuImage::Grave,
-                }));
+                })));
             }
         }
         if let Err(UpdateError::OccupiedBy(entity)) = self
@@ -940,20 +1501,26 @@ impl Game {
         {
             if self.world.components.ghost.contains(entity) {
                 self.take_damage();
-                self.ghost_message();
+                self.event(GameEvent::DamageTaken(DamageSource::Ghost));
                 if self.stats.health.is_empty() {
-                    return Some(GameControlFlow::GameOver(GameOverReason::KilledByGhost));
+                    return Ok(Some(GameControlFlow::GameOver(
+                        GameOverReason::KilledByGhost,
+                    )));
                 }
+                return Ok(None);
             }
             if self.world.components.beast.contains(entity) {
                 self.take_damage();
-                self.beast_message();
+                self.event(GameEvent::DamageTaken(DamageSource::Beast));
                 if self.stats.health.is_empty() {
-                    return Some(GameControlFlow::GameOver(GameOverReason::KilledByBeast));
+                    return Ok(Some(GameControlFlow::GameOver(
+                        GameOverReason::KilledByBeast,
+                    )));
                 }
+                return Ok(None);
             }
 
-            if self.world.components.unimportant_npc.contains(entity) {
+            if self.world.components.unimportant_npc.contains(entity) && !config.demo {
                 let text_options = vec![
                     "I think you would be happier if you went to the ocean.",
                     "Why don't you take a trip to the ocean. I hear it's wonderful this time of year.",
@@ -961,11 +1528,11 @@ impl Game {
                 ];
                 let text_str = text_options.choose(&mut self.rng).unwrap();
                 let text = format!("Townsperson:\n\n{}", text_str);
-                return Some(GameControlFlow::Menu(Menu {
+                return Ok(Some(GameControlFlow::Menu(Menu {
                     choices: vec![MenuChoice::SayNothing],
                     text,
                     image: MenuImage::Townsperson,
-                }));
+                })));
             }
             if !self.world.components.part_of_boat.contains(entity) {
                 if let Some(&npc) = self.world.components.npc.get(entity) {
@@ -998,11 +1565,11 @@ impl Game {
                         )
                     };
                     let image = MenuImage::Npc(npc);
-                    return Some(GameControlFlow::Menu(Menu {
+                    return Ok(Some(GameControlFlow::Menu(Menu {
                         choices,
                         text,
                         image,
-                    }));
+                    })));
                 }
             }
             if let Some(&shop_i) = self.world.components.shop.get(entity) {
@@ -1018,22 +1585,31 @@ impl Game {
                 let text =
                     format!("Innkeeper:\n\n{description}\n\n\nYou currently have {junk} junk.");
                 let image = MenuImage::Shop;
-                let choices = vec![
-                    MenuChoice::BuyFuel {
-                        cost: 2,
-                        amount: 200,
-                    },
-                    MenuChoice::BuyCrewCapacity(self.num_seats),
+                let mut choices = vec![MenuChoice::BuyFuel {
+                    cost: 2,
+                    amount: 200,
+                }];
+                if self.num_seats < self.hull.num_seats() {
+                    choices.push(MenuChoice::BuyCrewCapacity(self.num_seats));
+                }
+                if !self.stats.hull_integrity.is_full() {
+                    choices.push(MenuChoice::RepairHull(1));
+                }
+                if Upgrade::all().iter().any(|&u| !self.has_upgrade(u)) {
+                    choices.push(MenuChoice::BoatUpgrades);
+                }
+                choices.extend([
                     MenuChoice::SleepUntilMorning(shop_i as u32),
                     MenuChoice::StayAtInnForever,
                     MenuChoice::Leave,
-                ];
-                return Some(GameControlFlow::Menu(Menu {
+                ]);
+                return Ok(Some(GameControlFlow::Menu(Menu {
                     choices,
                     text,
                     image,
-                }));
+                })));
             }
+            return Err(ActionError::BlockedByCharacter);
         }
         if let Layers {
             item: Some(item), ..
@@ -1044,12 +1620,11 @@ impl Game {
                 let entity_data = self.world.components.remove_entity_data(*item);
                 self.world.spatial_table.remove(*item);
                 if let Some(junk) = entity_data.junk {
-                    let name = junk.name();
-                    self.messages.push(format!("You pick up the {name}."));
+                    self.event(GameEvent::ItemPickedUp(junk));
                 }
             }
         }
-        None
+        Ok(None)
     }
 
     pub fn is_in_dungeon(&self) -> bool {
@@ -1075,6 +1650,7 @@ impl Game {
             spawn: dungeon_state.dungeon_spawn,
         });
         self.visibility_grid = dungeon_state.visibility_grid;
+        self.rebuild_explore_frontier();
     }
 
     fn enter_dungeon(&mut self, dungeon_index: usize) {
@@ -1104,6 +1680,7 @@ impl Game {
             visibility_grid,
         };
         self.dungeon_state = Some(dungeon_state);
+        self.explore_frontier.clear();
     }
 
     fn is_player_on_driving_coord(&self) -> bool {
@@ -1129,10 +1706,14 @@ impl Game {
                 if let Some(dest) = line_2d::coords_between(coord, player_coord).skip(1).next() {
                     if dest == player_coord {
                         self.take_damage();
-                        self.ghost_message();
+                        self.event(GameEvent::DamageTaken(DamageSource::Ghost));
                         if self.stats.health.is_empty() {
                             return Some(GameControlFlow::GameOver(GameOverReason::KilledByGhost));
                         }
+                    } else if self.has_upgrade(Upgrade::ReinforcedHull)
+                        && self.is_coord_part_of_boat(dest)
+                    {
+                        // the ghost is stuck outside the hull
                     } else {
                         let _ = self.world.spatial_table.update_coord(entity, dest);
                     }
@@ -1168,17 +1749,19 @@ impl Game {
                     true
                 }
             }
-            if !self.is_sneak() {
+            if !self.is_effect_active(EffectKind::Sneak) {
                 self.ai_ctx.distance_map.clear();
                 self.ai_ctx.distance_map.add(self.player_coord());
-                if self.is_fear() {
+                if self.is_effect_active(EffectKind::Fear) {
                     let c = C {
                         components: &self.world.components,
                         spatial_table: &self.world.spatial_table,
                     };
-                    self.ai_ctx
-                        .distance_map
-                        .populate_flee(&c, 6, &mut self.world.distance_map);
+                    self.ai_ctx.distance_map.populate_flee(
+                        &c,
+                        BEAST_FLEE_DISTANCE,
+                        &mut self.world.distance_map,
+                    );
                 } else {
                     let c = C {
                         components: &self.world.components,
@@ -1186,7 +1769,7 @@ impl Game {
                     };
                     self.ai_ctx.distance_map.populate_approach(
                         &c,
-                        12,
+                        BEAST_APPROACH_DISTANCE,
                         &mut self.world.distance_map,
                     );
                 }
@@ -1199,7 +1782,7 @@ impl Game {
                             let destination = coord + direction.coord();
                             if destination == self.player_coord() {
                                 self.take_damage();
-                                self.beast_message();
+                                self.event(GameEvent::DamageTaken(DamageSource::Beast));
                                 if self.stats.health.is_empty() {
                                     return Some(GameControlFlow::GameOver(
                                         GameOverReason::KilledByBeast,
@@ -1216,22 +1799,13 @@ impl Game {
         None
     }
 
-    fn ghost_message(&mut self) {
-        self.messages.push(format!(
-            "A chill runs down your spine. The ghost deals you 1 damage."
-        ));
-    }
-
-    fn beast_message(&mut self) {
-        self.messages.push(format!("The beast deals you 1 damage."));
-    }
-
     fn add_npc_to_passengers_(&mut self, npc: Npc) {
         self.passengers.push(npc);
-        let uses = npc.ability_uses();
+        let uses = npc.ability().charges();
         let meter = Meter::new(uses, uses);
         self.npc_actions.insert(npc, meter);
         self.victory_stats.num_passengers += 1;
+        self.event(GameEvent::PassengerBoarded(npc));
     }
 
     pub fn npc_action(&self, npc: Npc) -> Option<&Meter> {
@@ -1278,76 +1852,61 @@ impl Game {
         None
     }
 
-    fn action_beast(&mut self) {
-        let turns = 30;
-        self.effect_timeouts.fear += turns + 1;
-        self.messages.push(format!(
-            "Beasts will flee from you for the next {turns} turns."
-        ));
-    }
-    fn action_ghost(&mut self) {
-        let turns = 30;
-        self.effect_timeouts.phase += turns + 1;
-        self.messages.push(format!(
-            "You can walk through walls (but not enemies) for the next {turns} turns."
-        ));
-    }
-    fn action_surgeon(&mut self) {
-        self.stats.health.fill();
-        self.messages.push(format!("Your wounds are fully healed."));
-    }
-    fn action_thief(&mut self) {
-        let turns = 30;
-        self.effect_timeouts.sneak += turns + 1;
-        self.messages.push(format!(
-            "Enemies will not react to you for the next {turns} turns."
-        ));
-    }
-    fn action_surveyor(&mut self) {
-        let turns = 30;
-        self.effect_timeouts.telescope += turns + 1;
-        self.messages.push(format!(
-            "You borrow the surveyor's telescope for {turns} turns"
-        ));
-    }
-
-    fn handle_ability(&mut self, index: u8) -> Option<GameControlFlow> {
-        let passenger_index = index as usize - 1;
-        if let Some(&npc) = self.passengers.get(passenger_index) {
-            if self.npc_has_action(npc) {
-                match npc {
-                    Npc::Physicist | Npc::Soldier => return Some(GameControlFlow::Aim(npc)),
-                    Npc::Beast => self.action_beast(),
-                    Npc::Ghost => self.action_ghost(),
-                    Npc::Surgeon => self.action_surgeon(),
-                    Npc::Thief => self.action_thief(),
-                    Npc::Surveyor => self.action_surveyor(),
-                };
-                self.npc_spend_action(npc);
-                self.pass_time();
-            } else {
-                self.messages
-                    .push(format!("{} has no remaining actions today.", npc.name()));
+    /// Apply the effect of an ability at a target. Instant abilities target the player.
+    fn apply_ability_effect(
+        &mut self,
+        effect: AbilityEffect,
+        target: Coord,
+    ) -> Result<Option<GameControlFlow>, ActionError> {
+        match effect {
+            AbilityEffect::Blast { radius } => self.ability_blast(target, radius),
+            AbilityEffect::Blink => self.ability_blink(target),
+            AbilityEffect::Heal => {
+                self.stats.health.fill();
+                self.event(GameEvent::Healed);
+                Ok(None)
+            }
+            AbilityEffect::StartEffect { kind, turns } => {
+                self.start_effect(kind, turns);
+                Ok(None)
             }
-        } else {
-            self.messages
-                .push(format!("There is no passenger in position {}.", index));
         }
-        None
+    }
+
+    fn handle_ability(&mut self, index: u8) -> Result<Option<GameControlFlow>, ActionError> {
+        let passenger_index = (index as usize).wrapping_sub(1);
+        let &npc = self
+            .passengers
+            .get(passenger_index)
+            .ok_or(ActionError::NoPassengerInSlot(index))?;
+        if !self.npc_has_action(npc) {
+            return Err(ActionError::NoAbilityCharges(npc));
+        }
+        let ability = npc.ability();
+        let maybe_cf = match ability.targeting() {
+            Targeting::Aimed => return Ok(Some(GameControlFlow::Aim(npc))),
+            Targeting::Instant => {
+                self.apply_ability_effect(ability.effect(), self.player_coord())?
+            }
+        };
+        self.npc_spend_action(npc);
+        self.pass_time();
+        Ok(maybe_cf)
     }
 
     #[must_use]
     pub(crate) fn handle_input(
         &mut self,
         input: Input,
-        _config: &Config,
+        config: &Config,
     ) -> Result<Option<GameControlFlow>, ActionError> {
+        self.events.clear();
         let game_control_flow = if self.driving {
             match input {
-                Input::Walk(CardinalDirection::East) => self.rotate_boat(RotateDirection::Right),
-                Input::Walk(CardinalDirection::West) => self.rotate_boat(RotateDirection::Left),
-                Input::Walk(CardinalDirection::North) => self.move_boat(MoveDirection::Forward),
-                Input::Walk(CardinalDirection::South) => self.move_boat(MoveDirection::Backward),
+                Input::Walk(CardinalDirection::East) => self.rotate_boat(RotateDirection::Right)?,
+                Input::Walk(CardinalDirection::West) => self.rotate_boat(RotateDirection::Left)?,
+                Input::Walk(CardinalDirection::North) => self.move_boat(MoveDirection::Forward)?,
+                Input::Walk(CardinalDirection::South) => self.move_boat(MoveDirection::Backward)?,
                 Input::DriveToggle => {
                     self.driving = false;
                     None
@@ -1356,22 +1915,23 @@ impl Game {
                     self.pass_time();
                     None
                 }
-                Input::Ability(i) => self.handle_ability(i),
+                Input::Ability(i) => self.handle_ability(i)?,
             }
         } else {
             match input {
-                Input::Walk(direction) => self.player_walk(direction),
+                Input::Walk(direction) => self.player_walk(direction, config)?,
                 Input::DriveToggle => {
-                    if self.is_player_on_driving_coord() {
-                        self.driving = true;
+                    if !self.is_player_on_driving_coord() {
+                        return Err(ActionError::NotAtBoatControls);
                     }
+                    self.driving = true;
                     None
                 }
                 Input::Wait => {
                     self.pass_time();
                     None
                 }
-                Input::Ability(i) => self.handle_ability(i),
+                Input::Ability(i) => self.handle_ability(i)?,
             }
         };
         if game_control_flow.is_some() {
@@ -1419,8 +1979,7 @@ impl Game {
         }
         self.stats.junk.decrease(cost);
         self.stats.fuel.increase(amount);
-        self.messages
-            .push(format!("You trade {cost} junk for {amount} fuel."));
+        self.event(GameEvent::FuelBought { cost, amount });
         None
     }
 
@@ -1430,9 +1989,110 @@ impl Game {
         }
         self.stats.junk.decrease(cost);
         self.num_seats += 1;
-        self.messages.push(format!(
-            "You trade {cost} junk for an additional passenger space."
-        ));
+        self.event(GameEvent::PassengerSpaceBought { cost });
+        None
+    }
+
+    fn repair_hull(&mut self, cost: u32) -> Option<GameControlFlow> {
+        if self.stats.junk.current() < cost {
+            return Some(self.cant_afford());
+        }
+        self.stats.junk.decrease(cost);
+        self.stats.hull_integrity.fill();
+        self.event(GameEvent::HullRepaired { cost });
+        None
+    }
+
+    fn boat_upgrades_menu(&self) -> GameControlFlow {
+        let junk = self.stats.junk.current();
+        let mut text = "Innkeeper:\n\n\
+            I can make some improvements to your boat, for a price.\n\n"
+            .to_string();
+        let mut choices = Vec::new();
+        for upgrade in Upgrade::all() {
+            if !self.has_upgrade(upgrade) {
+                let name = upgrade.name();
+                let description = upgrade.description();
+                text.push_str(&format!("{name}: {description}\n"));
+                choices.push(MenuChoice::BuyUpgrade {
+                    upgrade,
+                    cost: upgrade.cost(),
+                });
+            }
+        }
+        text.push_str(&format!("\n\nYou currently have {junk} junk."));
+        choices.push(MenuChoice::Leave);
+        let image = MenuImage::Shop;
+        GameControlFlow::Menu(Menu {
+            text,
+            choices,
+            image,
+        })
+    }
+
+    /// Rebuild the boat with a different hull where it is. Returns false and leaves the boat as
+    /// it was if the new hull doesn't fit.
+    fn try_change_hull(&mut self, hull: Hull) -> bool {
+        let (boat_entity, boat) = match self.world.components.boat.iter().next() {
+            Some((boat_entity, boat)) => (boat_entity, boat.clone()),
+            None => return false,
+        };
+        let boat_coord = match self.world.spatial_table.coord_of(boat_entity) {
+            Some(boat_coord) => boat_coord,
+            None => return false,
+        };
+        let previous = std::mem::replace(&mut self.hull, hull);
+        if self.try_rasterize_boat(boat_entity, boat, boat_coord) {
+            true
+        } else {
+            self.hull = previous;
+            false
+        }
+    }
+
+    fn no_room_for_hull(&self) -> GameControlFlow {
+        let text = "Innkeeper:\n\n\
+            There isn't enough room to rebuild your boat where it's moored. Bring it into more open \
+            water and come back."
+            .to_string();
+        let choices = vec![MenuChoice::Okay];
+        let image = MenuImage::Shop;
+        GameControlFlow::Menu(Menu {
+            text,
+            choices,
+            image,
+        })
+    }
+
+    fn buy_upgrade(&mut self, upgrade: Upgrade, cost: u32) -> Option<GameControlFlow> {
+        if self.stats.junk.current() < cost {
+            return Some(self.cant_afford());
+        }
+        if upgrade == Upgrade::Houseboat {
+            let hull = Hull::get(HOUSEBOAT_HULL).expect("missing houseboat hull");
+            if !self.try_change_hull(hull) {
+                return Some(self.no_room_for_hull());
+            }
+        }
+        self.stats.junk.decrease(cost);
+        self.upgrades.insert(upgrade);
+        match upgrade {
+            Upgrade::FuelTank => {
+                let fuel_max = self.stats.fuel.max();
+                self.stats.fuel.set_max(fuel_max + FUEL_TANK_CAPACITY);
+            }
+            Upgrade::ReinforcedHull => {
+                let hull_integrity_max = self.stats.hull_integrity.max();
+                self.stats
+                    .hull_integrity
+                    .set_max(hull_integrity_max + REINFORCED_HULL_INTEGRITY);
+                self.stats
+                    .hull_integrity
+                    .increase(REINFORCED_HULL_INTEGRITY);
+            }
+            Upgrade::Engine | Upgrade::Lantern | Upgrade::Houseboat => (),
+        }
+        self.event(GameEvent::UpgradeBought { upgrade, cost });
         None
     }
 
@@ -1444,15 +2104,19 @@ impl Game {
         }
     }
 
-    fn ability_soldier(&mut self, target: Coord) -> Result<Option<GameControlFlow>, String> {
-        let radius = 4;
+    fn ability_blast(
+        &mut self,
+        target: Coord,
+        radius: u32,
+    ) -> Result<Option<GameControlFlow>, ActionError> {
         let blob = procgen::blob(target, Size::new(radius, radius), &mut self.rng);
         let player_coord = self.player_coord();
+        if blob.inside.contains(&player_coord) {
+            return Err(ActionError::TargetTooClose);
+        }
+        self.event(GameEvent::Blast);
         let mut to_remove = Vec::new();
         for coord in blob.inside {
-            if coord == player_coord {
-                return Err(format!("Within minimum safe distance. Refusing to fire"));
-            }
             if let Some(&layers) = self.world.spatial_table.layers_at(coord) {
                 if let Some(floor) = layers.floor {
                     self.world.components.tile.insert(floor, Tile::BurntFloor);
@@ -1471,15 +2135,14 @@ impl Game {
         }
         for e in to_remove {
             if self.world.components.beast.contains(e) {
-                self.messages
-                    .push(format!("The beast is destroyed by the blast."));
+                self.event(GameEvent::BeastDestroyed);
             }
             self.world.components.remove_entity(e);
             self.world.spatial_table.remove(e);
         }
         Ok(None)
     }
-    fn ability_physicist(&mut self, target: Coord) -> Result<Option<GameControlFlow>, String> {
+    fn ability_blink(&mut self, target: Coord) -> Result<Option<GameControlFlow>, ActionError> {
         if self.driving {
             let (boat_entity, boat) = self.world.components.boat.iter().next().unwrap();
             let moved_boat = self.try_rasterize_boat(boat_entity, boat.clone(), target);
@@ -1488,9 +2151,9 @@ impl Game {
                     .world
                     .spatial_table
                     .update_coord(self.player_entity, target);
-                self.messages.push(format!("You blink."));
+                self.event(GameEvent::Blinked);
             } else {
-                return Err(format!("Destination is not empty!"));
+                return Err(ActionError::DestinationNotEmpty);
             }
         } else {
             let mut can_teleport = true;
@@ -1513,44 +2176,42 @@ impl Game {
                     .world
                     .spatial_table
                     .update_coord(self.player_entity, target);
-                self.messages.push(format!("You blink."));
+                self.event(GameEvent::Blinked);
             } else {
-                return Err(format!("Destination is not empty!"));
+                return Err(ActionError::DestinationNotEmpty);
             }
         }
         Ok(None)
     }
 
-    pub(crate) fn handle_aim(&mut self, npc: Npc, coord: Coord) -> Option<GameControlFlow> {
+    pub(crate) fn handle_aim(
+        &mut self,
+        npc: Npc,
+        coord: Coord,
+    ) -> Result<Option<GameControlFlow>, ActionError> {
+        self.events.clear();
         self.update_visibility();
-        if self.is_coord_visible(coord) {
-            let result = match npc {
-                Npc::Soldier => self.ability_soldier(coord),
-                Npc::Physicist => self.ability_physicist(coord),
-                _ => return None, // shouldn't get here
-            };
-            match result {
-                Ok(maybe_cf) => {
-                    self.npc_spend_action(npc);
-                    self.pass_time();
-                    if let Some(cf) = self.npc_turn() {
-                        return Some(cf);
-                    }
-                    self.update_visibility();
-                    maybe_cf
-                }
-                Err(message) => {
-                    self.messages.push(message);
-                    None
-                }
+        if !self.is_coord_visible(coord) {
+            return Err(ActionError::TargetNotVisible);
+        }
+        let ability = npc.ability();
+        if let Some(range) = ability.range() {
+            if coord.distance2(self.player_coord()) > range * range {
+                return Err(ActionError::TargetOutOfRange);
             }
-        } else {
-            self.messages.push(format!("Can't see the target."));
-            None
         }
+        let maybe_cf = self.apply_ability_effect(ability.effect(), coord)?;
+        self.npc_spend_action(npc);
+        self.pass_time();
+        if let Some(cf) = self.npc_turn() {
+            return Ok(Some(cf));
+        }
+        self.update_visibility();
+        Ok(maybe_cf)
     }
 
     pub(crate) fn handle_choice(&mut self, choice: MenuChoice) -> Option<GameControlFlow> {
+        self.events.clear();
         match choice {
             MenuChoice::DontAddNpcToPassengers
             | MenuChoice::Leave
@@ -1572,6 +2233,17 @@ impl Game {
                     return Some(cf);
                 }
             }
+            MenuChoice::RepairHull(cost) => {
+                if let Some(cf) = self.repair_hull(cost) {
+                    return Some(cf);
+                }
+            }
+            MenuChoice::BoatUpgrades => return Some(self.boat_upgrades_menu()),
+            MenuChoice::BuyUpgrade { upgrade, cost } => {
+                if let Some(cf) = self.buy_upgrade(upgrade, cost) {
+                    return Some(cf);
+                }
+            }
             _ => (),
         }
         // TODO this should be a function
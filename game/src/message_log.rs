@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// The number of messages kept before the oldest are forgotten
+pub const MESSAGE_LOG_CAPACITY: usize = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub day: u32,
+    pub turn: u64,
+    pub message: String,
+}
+
+/// Every message shown to the player, oldest first. Unlike the messages shown under the map,
+/// the log survives the start of a new day.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct MessageLog {
+    entries: VecDeque<LogEntry>,
+}
+
+impl MessageLog {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &LogEntry> + ExactSizeIterator {
+        self.entries.iter()
+    }
+
+    pub(crate) fn push(&mut self, entry: LogEntry) {
+        if self.entries.len() >= MESSAGE_LOG_CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+}
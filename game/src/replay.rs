@@ -0,0 +1,215 @@
+use crate::{
+    witness::{self, Witness},
+    Config, DebugCommand, Input, MenuChoice, Victory,
+};
+use coord_2d::Coord;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// A single interaction with the witness API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ReplayEvent {
+    Input(Input),
+    /// Consecutive ticks are merged into a single event to keep replays small. The game doesn't
+    /// depend on the duration of individual ticks so replaying `count` ticks of equal length
+    /// reproduces the same state.
+    Ticks {
+        count: u32,
+        total: Duration,
+    },
+    MenuChoice(MenuChoice),
+    MenuCancel,
+    Aim(Coord),
+    AimCancel,
+    Debug(DebugCommand),
+}
+
+/// Everything needed to deterministically recreate a game: the seed it was generated from, the
+/// victories used to populate the graveyard, and every event passed to the witness API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    pub victories: Vec<Victory>,
+    pub events: Vec<ReplayEvent>,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    /// The event at `index` can't be applied in the current state of the game. This means the
+    /// replayed game has diverged from the recorded game.
+    Desync { index: usize, event: ReplayEvent },
+}
+
+impl Replay {
+    pub fn new(seed: u64, victories: Vec<Victory>) -> Self {
+        Self {
+            seed,
+            victories,
+            events: Vec::new(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, event: ReplayEvent) {
+        self.events.push(event);
+    }
+
+    pub(crate) fn push_tick(&mut self, since_last_tick: Duration) {
+        if let Some(ReplayEvent::Ticks { count, total }) = self.events.last_mut() {
+            *count += 1;
+            *total += since_last_tick;
+        } else {
+            self.events.push(ReplayEvent::Ticks {
+                count: 1,
+                total: since_last_tick,
+            });
+        }
+    }
+
+    pub fn num_turns(&self) -> usize {
+        self.events
+            .iter()
+            .filter(|e| matches!(e, ReplayEvent::Input(_)))
+            .count()
+    }
+
+    /// Create a new game from the replay's seed and drive it through the recorded events,
+    /// returning the game and the witness it ended on.
+    pub fn run(&self, config: &Config) -> Result<(witness::Game, Witness), ReplayError> {
+        let (mut game, running) =
+            witness::new_game_with_seed(config, self.victories.clone(), self.seed);
+        let mut witness = running.into_witness();
+        for (index, event) in self.events.iter().enumerate() {
+            witness = Self::apply(&mut game, witness, event, config).ok_or_else(|| {
+                ReplayError::Desync {
+                    index,
+                    event: event.clone(),
+                }
+            })?;
+        }
+        Ok((game, witness))
+    }
+
+    fn apply(
+        game: &mut witness::Game,
+        witness: Witness,
+        event: &ReplayEvent,
+        config: &Config,
+    ) -> Option<Witness> {
+        let witness = match witness {
+            // The frontend keeps running the game after a win while it plays an animation
+            Witness::Win(win) => Witness::Running(win.into_running()),
+            other => other,
+        };
+        let witness = match (witness, event) {
+            (Witness::Running(running), &ReplayEvent::Input(input)) => {
+                let (witness, _) = match input {
+                    Input::Walk(direction) => running.walk(game, direction, config),
+                    Input::Wait => running.wait(game, config),
+                    Input::DriveToggle => running.drive_toggle(game, config),
+                    Input::Ability(index) => running.ability(game, config, index),
+                };
+                witness
+            }
+            (Witness::Running(running), &ReplayEvent::Ticks { count, total }) => {
+                let since_last_tick = total / count.max(1);
+                let mut witness = Witness::Running(running);
+                for _ in 0..count {
+                    witness = match witness {
+                        Witness::Running(running) => running.tick(game, since_last_tick, config),
+                        Witness::Win(win) => win.into_running().tick(game, since_last_tick, config),
+                        other => return Some(other),
+                    };
+                }
+                witness
+            }
+            (Witness::Aim(aim), &ReplayEvent::Ticks { count, total }) => {
+                // The frontend keeps the game ticking while the player is aiming
+                let since_last_tick = total / count.max(1);
+                let mut witness = Witness::Aim(aim);
+                for _ in 0..count {
+                    witness = match witness {
+                        Witness::Aim(aim) => aim.tick(game, since_last_tick, config),
+                        other => return Some(other),
+                    };
+                }
+                witness
+            }
+            (Witness::Menu(menu), ReplayEvent::MenuChoice(choice)) => {
+                menu.commit(game, choice.clone())
+            }
+            (Witness::Menu(menu), ReplayEvent::MenuCancel) => menu.cancel(game),
+            (Witness::Aim(aim), &ReplayEvent::Aim(coord)) => aim.commit(game, coord).0,
+            (Witness::Aim(aim), ReplayEvent::AimCancel) => aim.cancel(game),
+            (Witness::Running(running), &ReplayEvent::Debug(command)) => {
+                // the command was accepted when it was recorded
+                let config = Config {
+                    debug: true,
+                    ..*config
+                };
+                running.debug(game, &config, command).0.into_witness()
+            }
+            _ => return None,
+        };
+        Some(witness)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use direction::CardinalDirection;
+
+    const SEED: u64 = 0x5eed;
+
+    /// Play a game through the witness API, stopping early if it leaves the running state
+    fn play(config: &Config) -> witness::Game {
+        let (mut game, running) = witness::new_game_with_seed(config, Vec::new(), SEED);
+        let mut witness = running.into_witness();
+        let since_last_tick = Duration::from_millis(16);
+        let directions = [
+            CardinalDirection::North,
+            CardinalDirection::East,
+            CardinalDirection::South,
+            CardinalDirection::West,
+        ];
+        for i in 0..200 {
+            let running = match witness {
+                Witness::Running(running) => running,
+                Witness::Win(win) => win.into_running(),
+                Witness::Menu(menu) => {
+                    witness = menu.cancel(&mut game);
+                    continue;
+                }
+                Witness::Aim(aim) => {
+                    witness = aim.cancel(&mut game);
+                    continue;
+                }
+                Witness::GameOver(_) => break,
+            };
+            let direction = directions[i % directions.len()];
+            witness = match i % 5 {
+                0 => running.wait(&mut game, config).0,
+                1 | 2 => running.tick(&mut game, since_last_tick, config),
+                _ => running.walk(&mut game, direction, config).0,
+            };
+        }
+        game
+    }
+
+    #[test]
+    fn replay_reproduces_recorded_game() {
+        let config = Config::default();
+        let recorded = play(&config);
+        let (replayed, _) = recorded.replay().run(&config).unwrap();
+        let (recorded, replayed) = (recorded.inner_ref(), replayed.inner_ref());
+        assert_eq!(recorded.player_coord(), replayed.player_coord());
+        assert_eq!(recorded.boat_coord(), replayed.boat_coord());
+        assert_eq!(recorded.current_day(), replayed.current_day());
+        assert_eq!(recorded.tile_counts(), replayed.tile_counts());
+        assert_eq!(recorded.messages(), replayed.messages());
+        assert_eq!(
+            bincode::serialize(recorded.stats()).unwrap(),
+            bincode::serialize(replayed.stats()).unwrap()
+        );
+    }
+}
@@ -7,6 +7,7 @@ use crate::{
     Entity,
 };
 use coord_2d::{Coord, Size};
+use direction::Direction;
 use entity_table::entity_data;
 use procgen::{
     generate, generate_dungeon, Dungeon as DungeonGen, DungeonCell, Spec, WaterType, WorldCell3,
@@ -58,6 +59,8 @@ impl Terrain {
         let tree_chance3 = 0.05f64;
         let rock_chance1 = 0.05f64;
         let rock_chance2 = 0.1f64;
+        let river_rock_chance = 0.004f64;
+        let shallows_chance = 0.3f64;
         let mut num_stairs = 0;
         for (coord, &cell) in g.world3.grid.enumerate() {
             let water_distance = *g.water_distance_map.distances.get_checked(coord);
@@ -67,6 +70,7 @@ impl Terrain {
                         if coord.x > g.world2.ocean_x_ofset as i32 - 5 {
                             if rng.gen::<f64>() < rock_chance1 {
                                 world.spawn_floor(coord);
+                                world.spawn_rock(coord);
                             } else {
                                 world.spawn_floor(coord);
                             }
@@ -78,6 +82,7 @@ impl Terrain {
                                     world.spawn_tree(coord);
                                 } else if rng.gen::<f64>() < rock_chance1 {
                                     world.spawn_floor(coord);
+                                    world.spawn_rock(coord);
                                 } else {
                                     world.spawn_floor(coord);
                                 }
@@ -86,6 +91,7 @@ impl Terrain {
                                     world.spawn_tree(coord);
                                 } else if rng.gen::<f64>() < rock_chance2 {
                                     world.spawn_floor(coord);
+                                    world.spawn_rock(coord);
                                 } else {
                                     world.spawn_floor(coord);
                                 }
@@ -93,10 +99,28 @@ impl Terrain {
                         }
                     }
                     WorldCell3::Water(WaterType::River) => {
-                        if rng.gen::<f64>() < water_visible_chance {
-                            world.spawn_water1(coord);
-                        } else {
-                            world.spawn_water2(coord);
+                        let current = *g.world2.currents.get_checked(coord);
+                        let is_bank = Direction::all().any(|d| {
+                            !matches!(
+                                g.world3.grid.get(coord + d.coord()),
+                                Some(WorldCell3::Water(_))
+                            )
+                        });
+                        // hazards only appear in the flowing part of the river so the lake and
+                        // towns are safe to navigate
+                        let water =
+                            if current.is_some() && is_bank && rng.gen::<f64>() < shallows_chance {
+                                world.spawn_shallows(coord)
+                            } else if rng.gen::<f64>() < water_visible_chance {
+                                world.spawn_water1(coord)
+                            } else {
+                                world.spawn_water2(coord)
+                            };
+                        if let Some(flow) = current {
+                            world.components.current.insert(water, flow);
+                            if rng.gen::<f64>() < river_rock_chance {
+                                world.spawn_river_rock(coord);
+                            }
                         }
                     }
                     WorldCell3::Water(WaterType::Ocean) => {
@@ -141,10 +165,13 @@ impl Terrain {
                         }
                     }
                     WorldCell3::Gate => {
-                        if rng.gen::<f64>() < water_visible_chance {
-                            world.spawn_water1(coord);
+                        let water = if rng.gen::<f64>() < water_visible_chance {
+                            world.spawn_water1(coord)
                         } else {
-                            world.spawn_water2(coord);
+                            world.spawn_water2(coord)
+                        };
+                        if let Some(flow) = *g.world2.currents.get_checked(coord) {
+                            world.components.current.insert(water, flow);
                         }
                         world.spawn_gate(coord);
                     }
@@ -0,0 +1,77 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Weather {
+    Clear,
+    Rain,
+    Fog,
+    Storm,
+}
+
+impl Weather {
+    pub fn name(self) -> String {
+        match self {
+            Self::Clear => format!("Clear"),
+            Self::Rain => format!("Rain"),
+            Self::Fog => format!("Fog"),
+            Self::Storm => format!("Storm"),
+        }
+    }
+
+    /// Message shown when the weather changes to this
+    pub fn message(self) -> String {
+        match self {
+            Self::Clear => format!("The sky clears."),
+            Self::Rain => format!("It starts to rain."),
+            Self::Fog => format!("A thick fog rolls in over the river."),
+            Self::Storm => format!("A storm breaks overhead!"),
+        }
+    }
+
+    /// Choose the weather for the next part of the day. Weather tends to persist, and storms
+    /// only develop out of rain.
+    pub(crate) fn next<R: Rng>(self, rng: &mut R) -> Self {
+        let transitions = match self {
+            Self::Clear => [(Self::Clear, 50), (Self::Rain, 25), (Self::Fog, 25)].as_slice(),
+            Self::Rain => [
+                (Self::Clear, 25),
+                (Self::Rain, 30),
+                (Self::Fog, 15),
+                (Self::Storm, 30),
+            ]
+            .as_slice(),
+            Self::Fog => [(Self::Clear, 30), (Self::Rain, 10), (Self::Fog, 60)].as_slice(),
+            Self::Storm => [(Self::Clear, 10), (Self::Rain, 60), (Self::Storm, 30)].as_slice(),
+        };
+        let total = transitions.iter().map(|&(_, weight)| weight).sum::<u32>();
+        let mut roll = rng.gen_range(0..total);
+        for &(weather, weight) in transitions {
+            if roll < weight {
+                return weather;
+            }
+            roll -= weight;
+        }
+        self
+    }
+
+    /// Reduce a squared vision distance according to how hard it is to see
+    pub(crate) fn vision_distance_squared(self, clear_distance_squared: u32) -> u32 {
+        let percent = match self {
+            Self::Clear => 100,
+            Self::Rain => 70,
+            Self::Fog => 25,
+            Self::Storm => 50,
+        };
+        (clear_distance_squared * percent) / 100
+    }
+
+    /// The number of turns between ghosts appearing at night
+    pub(crate) fn ghost_spawn_interval(self) -> u32 {
+        match self {
+            Self::Clear | Self::Rain => 20,
+            Self::Fog => 12,
+            Self::Storm => 15,
+        }
+    }
+}
@@ -1,4 +1,8 @@
-use crate::{ActionError, Config, GameControlFlow, GameOverReason, Input, Menu as GameMenu, Npc};
+use crate::{
+    replay::{Replay, ReplayEvent},
+    ActionError, Config, DebugCommand, DebugError, GameControlFlow, GameOverReason, Input,
+    Menu as GameMenu, Npc,
+};
 use coord_2d::Coord;
 use direction::CardinalDirection;
 use rand::Rng;
@@ -7,29 +11,38 @@ use std::time::Duration;
 
 pub struct Game {
     inner_game: crate::Game,
+    replay: Replay,
 }
 
+/// A game in a form that can be saved. The replay grows for the whole run, so it's separated
+/// from the game to be stored on its own.
 #[derive(Serialize, Deserialize)]
 pub struct RunningGame {
     game: crate::Game,
 }
 
 impl RunningGame {
-    pub fn new(game: Game, running: Running) -> Self {
+    pub fn new(game: Game, running: Running) -> (Self, Replay) {
         let _ = running;
-        Self {
-            game: game.inner_game,
-        }
-    }
-
-    pub fn into_game(self) -> (Game, Running) {
         (
-            Game {
-                inner_game: self.game,
+            Self {
+                game: game.inner_game,
             },
-            Running(Private),
+            game.replay,
         )
     }
+
+    pub fn game(&self) -> &crate::Game {
+        &self.game
+    }
+
+    /// Resume the game, continuing to record the replay it was separated from. Settings from
+    /// the config which aren't saved with the game are applied again.
+    pub fn into_game(self, replay: Replay, config: &Config) -> (Game, Running) {
+        let mut inner_game = self.game;
+        inner_game.resume(config);
+        (Game { inner_game, replay }, Running(Private))
+    }
 }
 
 #[derive(Debug)]
@@ -69,8 +82,9 @@ impl Witness {
 }
 
 impl Menu {
-    pub fn cancel(self) -> Witness {
+    pub fn cancel(self, game: &mut Game) -> Witness {
         let Self { private, .. } = self;
+        game.replay.push(ReplayEvent::MenuCancel);
         Witness::running(private)
     }
     pub fn commit(self, game: &mut Game, choice: crate::MenuChoice) -> Witness {
@@ -80,14 +94,24 @@ impl Menu {
 }
 
 impl Aim {
-    pub fn cancel(self) -> Witness {
+    pub fn cancel(self, game: &mut Game) -> Witness {
         let Self { private, .. } = self;
+        game.replay.push(ReplayEvent::AimCancel);
         Witness::running(private)
     }
-    pub fn commit(self, game: &mut Game, coord: Coord) -> Witness {
+    pub fn commit(self, game: &mut Game, coord: Coord) -> (Witness, Result<(), ActionError>) {
         let Self { private, npc } = self;
         game.witness_handle_aim(npc, coord, private)
     }
+    /// The game keeps running while the player aims. Aiming continues unless the tick ends the
+    /// game or pre-empts the aim with something else.
+    pub fn tick(self, game: &mut Game, since_last_tick: Duration, config: &Config) -> Witness {
+        let Self { private, npc } = self;
+        match game.witness_handle_tick(since_last_tick, config, private) {
+            Witness::Running(Running(private)) => Witness::Aim(Self { private, npc }),
+            other => other,
+        }
+    }
 }
 
 pub enum ControlInput {
@@ -100,8 +124,20 @@ pub fn new_game<R: Rng>(
     victories: Vec<crate::Victory>,
     base_rng: &mut R,
 ) -> (Game, Running) {
+    new_game_with_seed(config, victories, base_rng.gen())
+}
+
+/// Like `new_game` but takes the seed that would otherwise be drawn from an rng. This is the seed
+/// stored in a game's replay, so a replay can recreate the game it was recorded from.
+pub fn new_game_with_seed(
+    config: &Config,
+    victories: Vec<crate::Victory>,
+    seed: u64,
+) -> (Game, Running) {
+    let replay = Replay::new(seed, victories.clone());
     let g = Game {
-        inner_game: crate::Game::new(config, victories, base_rng),
+        inner_game: crate::Game::new(config, victories, seed),
+        replay,
     };
     (g, Running(Private))
 }
@@ -164,6 +200,22 @@ impl Running {
         let Self(private) = self;
         game.witness_handle_input(Input::Ability(index), config, private)
     }
+
+    /// Apply a debug command, returning a message describing its effect. Commands are rejected
+    /// unless `Config::debug` is set.
+    pub fn debug(
+        self,
+        game: &mut Game,
+        config: &Config,
+        command: DebugCommand,
+    ) -> (Running, Result<String, DebugError>) {
+        if !config.debug {
+            return (self, Err(DebugError::Disabled));
+        }
+        game.replay.push(ReplayEvent::Debug(command));
+        let result = game.inner_game.handle_debug_command(command);
+        (self, result)
+    }
 }
 
 impl Game {
@@ -173,6 +225,7 @@ impl Game {
         config: &Config,
         private: Private,
     ) -> (Witness, Result<(), ActionError>) {
+        self.replay.push(ReplayEvent::Input(input));
         match self.inner_game.handle_input(input, config) {
             Err(e) => (Witness::running(private), Err(e)),
             Ok(None) => (Witness::running(private), Ok(())),
@@ -205,25 +258,39 @@ impl Game {
         config: &Config,
         private: Private,
     ) -> Witness {
+        self.replay.push_tick(since_last_tick);
         let control_flow = self.inner_game.handle_tick(since_last_tick, config);
         self.handle_control_flow(control_flow, private)
     }
 
     fn witness_handle_choice(&mut self, choice: crate::MenuChoice, private: Private) -> Witness {
+        self.replay.push(ReplayEvent::MenuChoice(choice.clone()));
         let control_flow = self.inner_game.handle_choice(choice);
         self.handle_control_flow(control_flow, private)
     }
 
-    fn witness_handle_aim(&mut self, npc: Npc, target: Coord, private: Private) -> Witness {
-        let control_flow = self.inner_game.handle_aim(npc, target);
-        self.handle_control_flow(control_flow, private)
+    fn witness_handle_aim(
+        &mut self,
+        npc: Npc,
+        target: Coord,
+        private: Private,
+    ) -> (Witness, Result<(), ActionError>) {
+        self.replay.push(ReplayEvent::Aim(target));
+        match self.inner_game.handle_aim(npc, target) {
+            Err(e) => (Witness::running(private), Err(e)),
+            Ok(control_flow) => (self.handle_control_flow(control_flow, private), Ok(())),
+        }
     }
 
     pub fn inner_ref(&self) -> &crate::Game {
         &self.inner_game
     }
 
-    pub fn into_running_game(self, running: Running) -> RunningGame {
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    pub fn into_running_game(self, running: Running) -> (RunningGame, Replay) {
         RunningGame::new(self, running)
     }
 }
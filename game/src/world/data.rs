@@ -29,11 +29,14 @@ declare_entity_module! {
         gate: (),
         beast: (),
         destructible: (),
+        current: Coord,
+        hazard: (),
+        shallows: (),
     }
 }
 pub use components::{Components, EntityData, EntityUpdate};
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Junk {
     RotaryPhone,
     BrokenTypewriter,
@@ -74,6 +77,7 @@ pub enum Tile {
     BoatFloor,
     Water1,
     Water2,
+    Shallows,
     Floor,
     BurntFloor,
     Wall,
@@ -96,6 +100,39 @@ pub enum Tile {
     Beast,
 }
 
+impl Tile {
+    /// What the player sees when examining the tile
+    pub fn description(self) -> String {
+        match self {
+            Self::Player => format!("yourself"),
+            Self::BoatEdge => format!("the side of your boat"),
+            Self::BoatFloor => format!("the deck of your boat"),
+            Self::Water1 | Self::Water2 => format!("murky river water"),
+            Self::Shallows => format!("shallow water over a sandbar"),
+            Self::Floor => format!("the floor"),
+            Self::BurntFloor => format!("scorched ground"),
+            Self::Wall => format!("a wall"),
+            Self::DoorClosed => format!("a closed door"),
+            Self::DoorOpen => format!("an open door"),
+            Self::Rock => format!("a rock"),
+            Self::Board => format!("the planks of your boat"),
+            Self::BoatControls => format!("the boat's controls"),
+            Self::Tree => format!("a tree"),
+            Self::StairsDown => format!("stairs leading down"),
+            Self::StairsUp => format!("stairs leading up"),
+            Self::Ghost => format!("a ghost"),
+            Self::UnimportantNpc => format!("a townsperson"),
+            Self::Grave => format!("a grave"),
+            Self::Npc(npc) => format!("the {}", npc.name().to_lowercase()),
+            Self::Junk => format!("some junk"),
+            Self::Shop => format!("the innkeeper"),
+            Self::Button => format!("the gate lever"),
+            Self::ButtonPressed => format!("the gate lever, already pulled"),
+            Self::Beast => format!("a beast"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Boat {
     heading: Radians,
@@ -232,28 +269,6 @@ impl Npc {
             Self::Surveyor => format!("Surveyor"),
         }
     }
-    pub fn ability_name(self) -> String {
-        match self {
-            Self::Soldier => format!("Destroy"),
-            Self::Physicist => format!("Blink"),
-            Self::Beast => format!("Fear"),
-            Self::Ghost => format!("Phase"),
-            Self::Surgeon => format!("Heal"),
-            Self::Thief => format!("Sneak"),
-            Self::Surveyor => format!("Telescope"),
-        }
-    }
-    pub fn ability_uses(self) -> u32 {
-        match self {
-            Self::Soldier => 2,
-            Self::Physicist => 2,
-            Self::Beast => 2,
-            Self::Ghost => 2,
-            Self::Surgeon => 2,
-            Self::Thief => 2,
-            Self::Surveyor => 2,
-        }
-    }
     pub fn text(self) -> String {
         let name = self.name();
         match self {
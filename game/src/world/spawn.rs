@@ -158,6 +158,38 @@ impl World {
         )
     }
 
+    pub fn spawn_rock(&mut self, coord: Coord) -> Entity {
+        self.spawn_entity(
+            (coord, Layer::Feature),
+            entity_data! {
+                tile: Tile::Rock,
+                solid: (),
+            },
+        )
+    }
+
+    /// A rock in the river which damages the boat's hull. Rocks on land only block the boat.
+    pub fn spawn_river_rock(&mut self, coord: Coord) -> Entity {
+        self.spawn_entity(
+            (coord, Layer::Feature),
+            entity_data! {
+                tile: Tile::Rock,
+                solid: (),
+                hazard: (),
+            },
+        )
+    }
+
+    pub fn spawn_shallows(&mut self, coord: Coord) -> Entity {
+        self.spawn_entity(
+            (coord, Layer::Water),
+            entity_data! {
+                tile: Tile::Shallows,
+                shallows: (),
+            },
+        )
+    }
+
     pub fn spawn_board(&mut self, coord: Coord) -> Entity {
         self.spawn_entity(
             (coord, Layer::Boat),
@@ -12,6 +12,7 @@ fn main() {
         storage,
         initial_rng_seed,
         omniscient,
+        debug,
         new_game,
     } = NativeCommon::parser()
         .with_help_default()
@@ -42,6 +43,7 @@ fn main() {
         storage,
         initial_rng_seed,
         omniscient,
+        debug,
         new_game,
     }));
 }
@@ -0,0 +1,135 @@
+use general_storage_file::{FileStorage, IfDirectoryMissing};
+use general_storage_static::{LoadRawError, RemoveError, Storage, StoreRawError};
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// Like `FileStorage`, except files are written to a temporary file which is then renamed over
+/// the original. Renaming is atomic, so if the game crashes while saving the previous version
+/// of the file is left intact rather than being partially overwritten.
+pub struct AtomicFileStorage {
+    file_storage: FileStorage,
+    base_path: PathBuf,
+}
+
+impl AtomicFileStorage {
+    pub fn new<P: AsRef<Path>>(
+        path: P,
+        if_directory_missing: IfDirectoryMissing,
+    ) -> io::Result<Self> {
+        let base_path = path.as_ref().to_path_buf();
+        let file_storage = FileStorage::new(path, if_directory_missing)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("{:?}", e)))?;
+        Ok(Self {
+            file_storage,
+            base_path,
+        })
+    }
+
+    pub fn next_to_exe<P: AsRef<Path>>(
+        path: P,
+        if_directory_missing: IfDirectoryMissing,
+    ) -> io::Result<Self> {
+        let exe_dir = std::env::current_exe()?
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        Self::new(exe_dir.join(path.as_ref()), if_directory_missing)
+    }
+
+    fn store_atomic(&self, key: &str, value: &[u8]) -> io::Result<()> {
+        let path = self.base_path.join(key);
+        let tmp_path = self.base_path.join(format!("{}.tmp", key));
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(value)?;
+        // make sure the contents are on disk before the old file is replaced
+        file.sync_all()?;
+        fs::rename(&tmp_path, &path)
+    }
+}
+
+impl Storage for AtomicFileStorage {
+    fn exists<K: AsRef<str>>(&self, key: K) -> bool {
+        self.file_storage.exists(key)
+    }
+
+    fn clear(&mut self) {
+        self.file_storage.clear()
+    }
+
+    fn remove<K: AsRef<str>>(&mut self, key: K) -> Result<(), RemoveError> {
+        self.file_storage.remove(key)
+    }
+
+    fn load_raw<K: AsRef<str>>(&self, key: K) -> Result<Vec<u8>, LoadRawError> {
+        self.file_storage.load_raw(key)
+    }
+
+    fn store_raw<K: AsRef<str>, V: AsRef<[u8]>>(
+        &mut self,
+        key: K,
+        value: V,
+    ) -> Result<(), StoreRawError> {
+        self.store_atomic(key.as_ref(), value.as_ref())
+            .map_err(|e| StoreRawError::IoError(Box::new(e)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A storage in a fresh directory which is deleted when the test finishes
+    struct TestStorage {
+        storage: AtomicFileStorage,
+        path: PathBuf,
+    }
+
+    impl TestStorage {
+        fn new(name: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("boat_journey_{}_{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&path);
+            let storage = AtomicFileStorage::new(&path, IfDirectoryMissing::Create).unwrap();
+            Self { storage, path }
+        }
+    }
+
+    impl Drop for TestStorage {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+
+    #[test]
+    fn store_and_load() {
+        let mut test = TestStorage::new("store_and_load");
+        assert!(!test.storage.exists("save"));
+        test.storage.store_raw("save", b"first").unwrap();
+        assert!(test.storage.exists("save"));
+        assert_eq!(test.storage.load_raw("save").unwrap(), b"first");
+    }
+
+    #[test]
+    fn overwrite_leaves_no_temporary_file() {
+        let mut test = TestStorage::new("overwrite");
+        test.storage.store_raw("save", b"first").unwrap();
+        test.storage.store_raw("save", b"second").unwrap();
+        assert_eq!(test.storage.load_raw("save").unwrap(), b"second");
+        assert!(!test.storage.exists("save.tmp"));
+    }
+
+    #[test]
+    fn remove() {
+        let mut test = TestStorage::new("remove");
+        test.storage.store_raw("save", b"first").unwrap();
+        test.storage.remove("save").unwrap();
+        assert!(!test.storage.exists("save"));
+        assert!(matches!(
+            test.storage.load_raw("save"),
+            Err(LoadRawError::NoSuchKey)
+        ));
+    }
+}
@@ -0,0 +1,135 @@
+//! Inspect and edit saved games. By default the save is printed as json. The "save" part of the
+//! json can be edited and written back with `--import`, which makes it possible to set up
+//! specific situations for testing without playing until they happen. The replay stored beside
+//! the save isn't part of the json, and is kept as it is when importing.
+
+use boat_journey_app::{AppStorage, GameInstanceStorable, SaveMetadata, SaveSlot, NUM_SAVE_SLOTS};
+use boat_journey_game::{Effects, Npc, Stats};
+use boat_journey_native::{meap, save_storage, DEFAULT_NEXT_TO_EXE_STORAGE_DIR, DEFAULT_SAVE_FILE};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, process};
+
+struct Args {
+    storage_dir: String,
+    save_file: String,
+    slot: usize,
+    output: Option<String>,
+    import: Option<String>,
+}
+
+impl Args {
+    fn parser() -> impl meap::Parser<Item = Self> {
+        meap::let_map! {
+            let {
+                storage_dir = opt_opt("PATH", 'd').name("storage-dir")
+                    .desc("directory that contains state")
+                    .with_default(DEFAULT_NEXT_TO_EXE_STORAGE_DIR.to_string());
+                save_file = opt_opt("PATH", 's').name("save-file").desc("save file for the first slot")
+                    .with_default(DEFAULT_SAVE_FILE.to_string());
+                slot = opt_opt("INT", "slot").desc("save slot (starting from 1)")
+                    .with_default(1);
+                output = opt_opt("PATH", 'o').name("output").desc("write json to this file instead of stdout");
+                import = opt_opt("PATH", 'i').name("import").desc("replace the save with the \"save\" part of this json file");
+            } in {{
+                Self {
+                    storage_dir,
+                    save_file,
+                    slot,
+                    output,
+                    import,
+                }
+            }}
+        }
+    }
+}
+
+/// Everything a tester is likely to want to know about a save, for reading rather than editing
+#[derive(Serialize)]
+struct Summary<'a> {
+    metadata: SaveMetadata,
+    stats: &'a Stats,
+    passengers: &'a [Npc],
+    effects: &'a Effects,
+    /// Number of entities with each tile in the current level
+    entities: BTreeMap<String, usize>,
+}
+
+#[derive(Serialize)]
+struct Dump<'a> {
+    /// Ignored when importing
+    summary: Summary<'a>,
+    save: &'a GameInstanceStorable,
+}
+
+#[derive(Deserialize)]
+struct Import {
+    save: GameInstanceStorable,
+}
+
+fn dump(storage: &AppStorage, slot: SaveSlot, output: Option<String>) -> Result<(), String> {
+    let (save, replay) = storage
+        .load_game(slot)
+        .map_err(|e| e.message())?
+        .ok_or_else(|| format!("Nothing is saved in {}.", slot.name()))?;
+    let game = save.game();
+    let dump = Dump {
+        summary: Summary {
+            metadata: save.metadata(&replay),
+            stats: game.stats(),
+            passengers: game.passengers(),
+            effects: game.effects(),
+            entities: game.tile_counts(),
+        },
+        save: &save,
+    };
+    let json = serde_json::to_string_pretty(&dump).map_err(|e| e.to_string())?;
+    match output {
+        Some(path) => fs::write(&path, json).map_err(|e| format!("{}: {}", path, e)),
+        None => {
+            println!("{}", json);
+            Ok(())
+        }
+    }
+}
+
+fn import(storage: &mut AppStorage, slot: SaveSlot, path: String) -> Result<(), String> {
+    let json = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e))?;
+    let Import { save } = serde_json::from_str(&json).map_err(|e| format!("{}: {}", path, e))?;
+    let replay = storage.load_slot_replay(slot).ok_or_else(|| {
+        format!(
+            "There is no replay in {} to import the save beside.",
+            slot.name()
+        )
+    })?;
+    // the previous save is kept as a backup
+    storage.save_game(slot, &save, &replay, &save.metadata(&replay));
+    eprintln!("Saved {} to {}.", path, slot.name());
+    Ok(())
+}
+
+fn main() {
+    use meap::Parser;
+    let Args {
+        storage_dir,
+        save_file,
+        slot,
+        output,
+        import: import_path,
+    } = Args::parser().with_help_default().parse_env_or_exit();
+    let slot = match SaveSlot::all().nth(slot.wrapping_sub(1)) {
+        Some(slot) => slot,
+        None => {
+            eprintln!("Save slots are numbered from 1 to {}.", NUM_SAVE_SLOTS);
+            process::exit(1);
+        }
+    };
+    let mut storage = save_storage(&storage_dir, save_file);
+    let result = match import_path {
+        Some(path) => import(&mut storage, slot, path),
+        None => dump(&storage, slot, output),
+    };
+    if let Err(message) = result {
+        eprintln!("{}", message);
+        process::exit(1);
+    }
+}
@@ -1,17 +1,41 @@
-use boat_journey_app::{AppStorage, InitialRngSeed};
-use general_storage_file::{FileStorage, IfDirectoryMissing};
-use general_storage_static::StaticStorage;
+use atomic_file_storage::AtomicFileStorage;
+use boat_journey_app::{AppStorage, InitialRngSeed, StorageHandle};
+use general_storage_file::IfDirectoryMissing;
+use general_storage_static::Storage;
 pub use meap;
 
-const DEFAULT_SAVE_FILE: &str = "save";
-const DEFAULT_NEXT_TO_EXE_STORAGE_DIR: &str = "save";
+mod atomic_file_storage;
+
+pub const DEFAULT_SAVE_FILE: &str = "save";
+pub const DEFAULT_NEXT_TO_EXE_STORAGE_DIR: &str = "save";
 const DEFAULT_CONFIG_FILE: &str = "config.json";
 const DEFAULT_CONTROLS_FILE: &str = "controls.json";
+const DEFAULT_REPLAY_FILE: &str = "replay";
+
+/// Open a directory next to the executable to store game state in
+pub fn open_storage(storage_dir: &str) -> StorageHandle {
+    StorageHandle::new(
+        AtomicFileStorage::next_to_exe(storage_dir, IfDirectoryMissing::Create)
+            .expect("failed to open directory"),
+    )
+}
+
+/// Storage for tools which work with saved games, using the default names for other files
+pub fn save_storage(storage_dir: &str, save_file: String) -> AppStorage {
+    AppStorage {
+        handle: open_storage(storage_dir),
+        save_game_key: save_file,
+        config_key: DEFAULT_CONFIG_FILE.to_string(),
+        controls_key: DEFAULT_CONTROLS_FILE.to_string(),
+        replay_key: DEFAULT_REPLAY_FILE.to_string(),
+    }
+}
 
 pub struct NativeCommon {
     pub storage: AppStorage,
     pub initial_rng_seed: InitialRngSeed,
     pub omniscient: bool,
+    pub debug: bool,
     pub new_game: bool,
 }
 impl NativeCommon {
@@ -19,32 +43,26 @@ impl NativeCommon {
         meap::let_map! {
             let {
                 rng_seed = opt_opt::<u64, _>("INT", 'r').name("rng-seed").desc("rng seed to use for first new game");
-                save_file = opt_opt("PATH", 's').name("save-file").desc("save file")
+                save_file = opt_opt("PATH", 's').name("save-file").desc("save file for the first slot (other slots are named after it)")
                     .with_default(DEFAULT_SAVE_FILE.to_string());
                 config_file = opt_opt("PATH", 'c').name("config-file").desc("config file")
                     .with_default(DEFAULT_CONFIG_FILE.to_string());
                 controls_file = opt_opt("PATH", "controls-file").desc("controls file")
                     .with_default(DEFAULT_CONTROLS_FILE.to_string());
+                replay_file = opt_opt("PATH", "replay-file").desc("file to record the replay of the current game in")
+                    .with_default(DEFAULT_REPLAY_FILE.to_string());
                 storage_dir = opt_opt("PATH", 'd').name("storage-dir")
                     .desc("directory that will contain state")
                     .with_default(DEFAULT_NEXT_TO_EXE_STORAGE_DIR.to_string());
-                delete_save = flag("delete-save").desc("delete save game file");
+                delete_save = flag("delete-save").desc("delete the save game in every slot");
                 delete_config = flag("delete-config").desc("delete config file");
                 delete_controls = flag("delete-controls").desc("delete controls file");
                 new_game = flag("new-game").desc("start a new game, skipping the menu");
                 omniscient = flag("omniscient").desc("enable omniscience");
+                debug = flag("debug").desc("enable the developer console (toggled with `)");
             } in {{
                 let initial_rng_seed = rng_seed.map(InitialRngSeed::U64).unwrap_or(InitialRngSeed::Random);
-                let mut file_storage = StaticStorage::new(
-                    FileStorage::next_to_exe(storage_dir, IfDirectoryMissing::Create)
-                    .expect("failed to open directory"),
-                );
-                if delete_save {
-                    let result = file_storage.remove(&save_file);
-                    if result.is_err() {
-                        log::warn!("couldn't find save file to delete");
-                    }
-                }
+                let mut file_storage = open_storage(&storage_dir);
                 if delete_config {
                     let result = file_storage.remove(&config_file);
                     if result.is_err() {
@@ -57,16 +75,21 @@ impl NativeCommon {
                         log::warn!("couldn't find controls file to delete");
                     }
                 }
-                let storage = AppStorage {
+                let mut storage = AppStorage {
                     handle: file_storage,
                     save_game_key: save_file,
                     config_key: config_file,
                     controls_key: controls_file,
+                    replay_key: replay_file,
                 };
+                if delete_save {
+                    storage.clear_all_games();
+                }
                 Self {
                     initial_rng_seed,
                     storage,
                     omniscient,
+                    debug,
                     new_game,
                 }
             }}
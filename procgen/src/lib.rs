@@ -453,6 +453,49 @@ pub struct World2 {
     pub city_centre: Coord,
     pub city_blob: Blob,
     pub gate: Vec<Coord>,
+    /// The direction water flows at each cell of the river channel as a unit vector
+    pub currents: Grid<Option<Coord>>,
+}
+
+/// The current carries water along the river from the lake towards the ocean. Each cell of the
+/// river channel takes its flow direction from the nearest point on the river's path. The lake
+/// and the pools in towns are still.
+fn make_currents(
+    grid: &Grid<WorldCell2>,
+    river_path_flow: &[(Coord, Coord)],
+    still_water: &HashSet<Coord>,
+) -> Grid<Option<Coord>> {
+    let max_distance = 8;
+    let is_flowing = |coord: Coord| {
+        !still_water.contains(&coord)
+            && grid.get(coord) == Some(&WorldCell2::Water(WaterType::River))
+    };
+    let mut currents = Grid::new_copy(grid.size(), None);
+    let mut distances = HashMap::new();
+    let mut to_visit = VecDeque::new();
+    for &(coord, flow) in river_path_flow {
+        if is_flowing(coord) && !distances.contains_key(&coord) {
+            distances.insert(coord, 0);
+            *currents.get_checked_mut(coord) = Some(flow);
+            to_visit.push_back(coord);
+        }
+    }
+    while let Some(coord) = to_visit.pop_front() {
+        let distance = distances[&coord] + 1;
+        if distance > max_distance {
+            continue;
+        }
+        let flow = *currents.get_checked(coord);
+        for d in Direction::all() {
+            let neighbour_coord = coord + d.coord();
+            if is_flowing(neighbour_coord) && !distances.contains_key(&neighbour_coord) {
+                distances.insert(neighbour_coord, distance);
+                *currents.get_checked_mut(neighbour_coord) = flow;
+                to_visit.push_back(neighbour_coord);
+            }
+        }
+    }
+    currents
 }
 
 fn make_world_grid2<R: Rng>(
@@ -486,9 +529,15 @@ fn make_world_grid2<R: Rng>(
     river.push_front(lake_coord_unscaled);
     river.push_back(river_end_unscaled);
     let river = river.into_iter().collect::<Vec<_>>();
+    let mut river_path_flow = Vec::new();
     for w in river.windows(2) {
-        for coord in line_2d::coords_between(scale_coord(w[0]), scale_coord(w[1])) {
+        let start = scale_coord(w[0]);
+        let end = scale_coord(w[1]);
+        let delta = end - start;
+        let flow = Coord::new(delta.x.signum(), delta.y.signum());
+        for coord in line_2d::coords_between(start, end) {
             *grid.get_checked_mut(coord) = WorldCell2::Water(WaterType::River);
+            river_path_flow.push((coord, flow));
         }
     }
     let widen_river = |grid: Grid<WorldCell2>| {
@@ -512,6 +561,7 @@ fn make_world_grid2<R: Rng>(
     for &coord in &lake.inside {
         *grid.get_checked_mut(coord) = WorldCell2::Water(WaterType::River);
     }
+    let mut still_water = lake.inside.iter().cloned().collect::<HashSet<_>>();
     let spawn = lake_coord;
     let town_size = TOWN_SIZE * zoom;
     let mut pool_centres = Vec::new();
@@ -523,6 +573,7 @@ fn make_world_grid2<R: Rng>(
         let town_blob = blob(town_coord, radius, rng);
         for &coord in &town_blob.inside {
             *grid.get_checked_mut(coord) = WorldCell2::Water(WaterType::River);
+            still_water.insert(coord);
         }
         town_blobs.push(town_blob);
     }
@@ -557,6 +608,7 @@ fn make_world_grid2<R: Rng>(
         .cloned()
         .filter(|coord| coord.distance2(gate_centre) < 400)
         .collect::<Vec<_>>();
+    let currents = make_currents(&grid, &river_path_flow, &still_water);
     //let spawn = scale_coord(river_end_unscaled) - Coord::new(right_ocean_padding as i32 + 10, 0);
     World2 {
         spawn,
@@ -567,6 +619,7 @@ fn make_world_grid2<R: Rng>(
         city_centre,
         city_blob,
         gate,
+        currents,
     }
 }
 
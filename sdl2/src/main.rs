@@ -10,6 +10,7 @@ fn main() {
         storage,
         initial_rng_seed,
         omniscient,
+        debug,
         new_game,
     } = NativeCommon::parser()
         .with_help_default()
@@ -41,6 +42,7 @@ fn main() {
         storage,
         initial_rng_seed,
         omniscient,
+        debug,
         new_game,
     }));
 }
@@ -0,0 +1,91 @@
+use crate::{Bot, View};
+use boat_journey_game::{
+    ActionError, Autopilot, AutopilotDestination, CardinalDirection, Coord, Input, Menu,
+    MenuChoice, Npc,
+};
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_isaac::Isaac64Rng;
+
+/// Number of random inputs to make after the autopilot gets stuck, before trying it again
+const WANDER_TURNS: u32 = 20;
+
+/// Walks to the boat and lets the autopilot drive it to the ocean. When the autopilot gets stuck
+/// it wanders randomly for a while and then tries again.
+pub struct AutopilotBot {
+    rng: Isaac64Rng,
+    autopilot: Option<Autopilot>,
+    wander_turns: u32,
+}
+
+impl AutopilotBot {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Isaac64Rng::seed_from_u64(seed),
+            autopilot: None,
+            wander_turns: 0,
+        }
+    }
+
+    fn wander(&mut self) -> Input {
+        use CardinalDirection::*;
+        Input::Walk(*[North, East, South, West].choose(&mut self.rng).unwrap())
+    }
+
+    fn drive(&mut self, view: View) -> Input {
+        if self.autopilot.is_none() {
+            match view.autopilot(AutopilotDestination::Ocean) {
+                Ok(autopilot) => {
+                    // The bot has no other way of getting fuel or fighting, so it never gives up
+                    self.autopilot = Some(autopilot.with_low_fuel(0).ignoring_danger())
+                }
+                Err(_) => {
+                    self.wander_turns = WANDER_TURNS;
+                    return self.wander();
+                }
+            }
+        }
+        let autopilot = self.autopilot.as_mut().unwrap();
+        match view.autopilot_direction(autopilot) {
+            Ok(direction) => Input::Walk(direction),
+            Err(_) => {
+                // Reaching the ocean ends the game on the next tick, so any other reason for
+                // stopping means the boat needs help getting unstuck
+                self.autopilot = None;
+                self.wander_turns = WANDER_TURNS;
+                self.wander()
+            }
+        }
+    }
+}
+
+impl Bot for AutopilotBot {
+    fn act(&mut self, view: View) -> Input {
+        if self.wander_turns > 0 {
+            self.wander_turns -= 1;
+            return self.wander();
+        }
+        if view.is_driving() {
+            return self.drive(view);
+        }
+        match view.boat_coord() {
+            Some(boat_coord) if boat_coord == view.player_coord() => Input::DriveToggle,
+            Some(boat_coord) => match view.direction_towards(boat_coord) {
+                Some(direction) => Input::Walk(direction),
+                None => self.wander(),
+            },
+            None => self.wander(),
+        }
+    }
+
+    fn choose(&mut self, _view: View, _menu: &Menu) -> Option<MenuChoice> {
+        None
+    }
+
+    fn aim(&mut self, _view: View, _npc: Npc) -> Option<Coord> {
+        None
+    }
+
+    fn rejected(&mut self, _view: View, _error: ActionError) {
+        self.autopilot = None;
+    }
+}
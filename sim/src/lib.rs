@@ -0,0 +1,258 @@
+use boat_journey_game::{
+    witness::{self, Witness},
+    ActionError, Autopilot, AutopilotDestination, AutopilotStop, CardinalDirection, CellVisibility,
+    Config, Coord, Effects, Game, GameEvent, GameOverReason, Input, Menu, MenuChoice, Meter, Npc,
+    Size, Stats, VictoryStats, VisibleCellData, Weather,
+};
+use std::time::Duration;
+
+mod autopilot_bot;
+mod random_bot;
+pub use autopilot_bot::AutopilotBot;
+pub use random_bot::RandomBot;
+
+/// The amount of time that passes between inputs. The game only uses ticks to notice that the
+/// player has reached the ocean so the exact value doesn't matter.
+pub const TICK_DURATION: Duration = Duration::from_millis(16);
+
+/// A read-only view of a game, as seen by a bot
+#[derive(Clone, Copy)]
+pub struct View<'a> {
+    game: &'a Game,
+}
+
+impl<'a> View<'a> {
+    pub fn new(game: &'a Game) -> Self {
+        Self { game }
+    }
+    pub fn player_coord(&self) -> Coord {
+        self.game.player_coord()
+    }
+    pub fn world_size(&self) -> Size {
+        self.game.world_size()
+    }
+    pub fn cell_visibility(&self, coord: Coord) -> CellVisibility<&'a VisibleCellData> {
+        self.game.cell_visibility_at_coord(coord)
+    }
+    pub fn stats(&self) -> &'a Stats {
+        self.game.stats()
+    }
+    pub fn passengers(&self) -> &'a [Npc] {
+        self.game.passengers()
+    }
+    pub fn num_seats(&self) -> u32 {
+        self.game.num_seats()
+    }
+    pub fn npc_action(&self, npc: Npc) -> Option<&'a Meter> {
+        self.game.npc_action(npc)
+    }
+    pub fn effects(&self) -> &'a Effects {
+        self.game.effects()
+    }
+    pub fn messages(&self) -> &'a [String] {
+        self.game.messages()
+    }
+    /// Everything that happened during the most recent action
+    pub fn events(&self) -> &'a [GameEvent] {
+        self.game.events()
+    }
+    pub fn current_day(&self) -> u32 {
+        self.game.current_day()
+    }
+    pub fn weather(&self) -> Weather {
+        self.game.weather()
+    }
+    pub fn is_driving(&self) -> bool {
+        self.game.is_driving()
+    }
+    pub fn is_player_on_boat(&self) -> bool {
+        self.game.is_player_on_boat()
+    }
+    pub fn is_player_inside(&self) -> bool {
+        self.game.is_player_inside()
+    }
+    pub fn is_in_dungeon(&self) -> bool {
+        self.game.is_in_dungeon()
+    }
+    pub fn is_player_outside_at_night(&self) -> bool {
+        self.game.is_player_outside_at_night()
+    }
+    pub fn boat_coord(&self) -> Option<Coord> {
+        self.game.boat_coord()
+    }
+    /// The direction to walk to get closer to a coord, through cells the player has seen
+    pub fn direction_towards(&self, destination: Coord) -> Option<CardinalDirection> {
+        self.game.direction_towards(destination)
+    }
+    /// Plan a route for the boat. The autopilot can then choose a direction to walk each turn.
+    pub fn autopilot(&self, destination: AutopilotDestination) -> Result<Autopilot, AutopilotStop> {
+        Autopilot::new(self.game, destination)
+    }
+    pub fn autopilot_direction(
+        &self,
+        autopilot: &mut Autopilot,
+    ) -> Result<CardinalDirection, AutopilotStop> {
+        autopilot.next_direction(self.game)
+    }
+}
+
+/// Plays the game in place of a person
+pub trait Bot {
+    /// Choose the next input while the game is running
+    fn act(&mut self, view: View) -> Input;
+
+    /// Choose an option from a menu, or `None` to close the menu
+    fn choose(&mut self, view: View, menu: &Menu) -> Option<MenuChoice>;
+
+    /// Choose a target for a passenger's ability, or `None` to cancel
+    fn aim(&mut self, view: View, npc: Npc) -> Option<Coord>;
+
+    /// Called when the game rejects an input or target chosen by the bot
+    fn rejected(&mut self, view: View, error: ActionError) {
+        let _ = (view, error);
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SimConfig {
+    pub game_config: Config,
+    /// Games which haven't ended after this many steps are abandoned. A step is a single input,
+    /// menu choice, or aim target, including those rejected by the game.
+    pub max_steps: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Win,
+    GameOver(GameOverReason),
+    StepLimit,
+}
+
+#[derive(Debug, Clone)]
+pub struct GameResult {
+    pub seed: u64,
+    pub outcome: Outcome,
+    pub victory_stats: VictoryStats,
+    pub num_rejected_actions: u64,
+}
+
+/// Play a single game to completion without rendering anything
+pub fn run_game<B: Bot + ?Sized>(bot: &mut B, config: &SimConfig, seed: u64) -> GameResult {
+    let game_config = &config.game_config;
+    let (mut game, running) = witness::new_game_with_seed(game_config, Vec::new(), seed);
+    let mut witness = running.into_witness();
+    let mut num_rejected_actions = 0;
+    let mut num_steps = 0;
+    let outcome = loop {
+        if num_steps == config.max_steps {
+            break Outcome::StepLimit;
+        }
+        num_steps += 1;
+        witness = match witness {
+            Witness::Win(_) => break Outcome::Win,
+            Witness::GameOver(reason) => break Outcome::GameOver(reason),
+            Witness::Running(running) => {
+                let (witness, result) = match bot.act(View::new(game.inner_ref())) {
+                    Input::Walk(direction) => running.walk(&mut game, direction, game_config),
+                    Input::Wait => running.wait(&mut game, game_config),
+                    Input::DriveToggle => running.drive_toggle(&mut game, game_config),
+                    Input::Ability(index) => running.ability(&mut game, game_config, index),
+                };
+                if let Err(error) = result {
+                    num_rejected_actions += 1;
+                    bot.rejected(View::new(game.inner_ref()), error);
+                }
+                match witness {
+                    Witness::Running(running) => {
+                        running.tick(&mut game, TICK_DURATION, game_config)
+                    }
+                    other => other,
+                }
+            }
+            Witness::Menu(menu) => match bot.choose(View::new(game.inner_ref()), &menu.menu) {
+                Some(choice) => menu.commit(&mut game, choice),
+                None => menu.cancel(&mut game),
+            },
+            Witness::Aim(aim) => match bot.aim(View::new(game.inner_ref()), aim.npc) {
+                Some(coord) => {
+                    let (witness, result) = aim.commit(&mut game, coord);
+                    if let Err(error) = result {
+                        num_rejected_actions += 1;
+                        bot.rejected(View::new(game.inner_ref()), error);
+                    }
+                    witness
+                }
+                None => aim.cancel(&mut game),
+            },
+        };
+    };
+    GameResult {
+        seed,
+        outcome,
+        victory_stats: game.inner_ref().victory_stats().clone(),
+        num_rejected_actions,
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Summary {
+    pub results: Vec<GameResult>,
+}
+
+impl Summary {
+    pub fn num_games(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn count(&self, outcome: Outcome) -> usize {
+        self.results.iter().filter(|r| r.outcome == outcome).count()
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        if self.results.is_empty() {
+            return 0.;
+        }
+        self.count(Outcome::Win) as f64 / self.num_games() as f64
+    }
+
+    /// The number of games that ended with each outcome, in the order each outcome first occurred
+    pub fn outcome_counts(&self) -> Vec<(Outcome, usize)> {
+        let mut counts: Vec<(Outcome, usize)> = Vec::new();
+        for result in &self.results {
+            if let Some((_, count)) = counts.iter_mut().find(|(o, _)| *o == result.outcome) {
+                *count += 1;
+            } else {
+                counts.push((result.outcome, 1));
+            }
+        }
+        counts
+    }
+
+    /// Mean number of turns taken in games with the given outcome
+    pub fn mean_turns(&self, outcome: Outcome) -> Option<f64> {
+        let turns = self
+            .results
+            .iter()
+            .filter(|r| r.outcome == outcome)
+            .map(|r| r.victory_stats.num_turns)
+            .collect::<Vec<_>>();
+        if turns.is_empty() {
+            None
+        } else {
+            Some(turns.iter().sum::<u64>() as f64 / turns.len() as f64)
+        }
+    }
+}
+
+/// Play a game for each seed, creating a fresh bot for each game
+pub fn run_games<B: Bot, F: FnMut(u64) -> B>(
+    mut make_bot: F,
+    config: &SimConfig,
+    seeds: &[u64],
+) -> Summary {
+    let results = seeds
+        .iter()
+        .map(|&seed| run_game(&mut make_bot(seed), config, seed))
+        .collect();
+    Summary { results }
+}
@@ -0,0 +1,127 @@
+use boat_journey_game::{Config, StatsConfig};
+use boat_journey_sim::{run_games, AutopilotBot, RandomBot, SimConfig};
+use rand::{Rng, SeedableRng};
+use rand_isaac::Isaac64Rng;
+
+#[derive(Debug, Clone, Copy)]
+enum BotKind {
+    Random,
+    Autopilot,
+}
+
+impl std::str::FromStr for BotKind {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "random" => Ok(Self::Random),
+            "autopilot" => Ok(Self::Autopilot),
+            other => Err(format!("unknown bot: {}", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for BotKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Random => write!(f, "random"),
+            Self::Autopilot => write!(f, "autopilot"),
+        }
+    }
+}
+
+struct Args {
+    bot: BotKind,
+    num_games: usize,
+    rng_seed: u64,
+    sim_config: SimConfig,
+    verbose: bool,
+}
+
+impl Args {
+    fn parser() -> impl meap::Parser<Item = Self> {
+        let defaults = StatsConfig::default();
+        meap::let_map! {
+            let {
+                bot = opt_opt("NAME", 'b').name("bot").desc("which bot to use (random or autopilot)")
+                    .with_default(BotKind::Random);
+                num_games = opt_opt("INT", 'n').name("num-games").desc("number of games to play")
+                    .with_default(100);
+                rng_seed = opt_opt::<u64, _>("INT", 'r').name("rng-seed").desc("rng seed")
+                    .with_default_lazy_general(|| rand::thread_rng().gen());
+                max_steps = opt_opt("INT", "max-steps").desc("abandon games after this many steps")
+                    .with_default(100_000);
+                health = opt_opt("INT", "health").desc("maximum health")
+                    .with_default(defaults.health);
+                fuel = opt_opt("INT", "fuel").desc("initial fuel")
+                    .with_default(defaults.fuel);
+                fuel_max = opt_opt("INT", "fuel-max").desc("maximum fuel")
+                    .with_default(defaults.fuel_max);
+                day_length = opt_opt("INT", "day-length").desc("number of turns of daylight")
+                    .with_default(defaults.day_length);
+                junk_max = opt_opt("INT", "junk-max").desc("maximum junk")
+                    .with_default(defaults.junk_max);
+                hull_integrity = opt_opt("INT", "hull-integrity").desc("maximum hull integrity")
+                    .with_default(defaults.hull_integrity);
+                verbose = flag("verbose").desc("print the result of each game");
+            } in {{
+                let game_config = Config {
+                    demo: true,
+                    stats: StatsConfig {
+                        health,
+                        fuel,
+                        fuel_max,
+                        day_length,
+                        junk_max,
+                        hull_integrity,
+                    },
+                    ..Default::default()
+                };
+                Self {
+                    bot,
+                    num_games,
+                    rng_seed,
+                    sim_config: SimConfig {
+                        game_config,
+                        max_steps,
+                    },
+                    verbose,
+                }
+            }}
+        }
+    }
+}
+
+fn main() {
+    use meap::Parser;
+    let Args {
+        bot,
+        num_games,
+        rng_seed,
+        sim_config,
+        verbose,
+    } = Args::parser().with_help_default().parse_env_or_exit();
+    println!("RNG Seed: {}", rng_seed);
+    let mut seed_rng = Isaac64Rng::seed_from_u64(rng_seed);
+    let seeds = (0..num_games).map(|_| seed_rng.gen()).collect::<Vec<u64>>();
+    let summary = match bot {
+        BotKind::Random => run_games(RandomBot::new, &sim_config, &seeds),
+        BotKind::Autopilot => run_games(AutopilotBot::new, &sim_config, &seeds),
+    };
+    if verbose {
+        for result in &summary.results {
+            println!(
+                "{}: {:?} after {} turns ({} rejected actions)",
+                result.seed,
+                result.outcome,
+                result.victory_stats.num_turns,
+                result.num_rejected_actions
+            );
+        }
+    }
+    println!("Games: {}", summary.num_games());
+    println!("Win rate: {:.1}%", summary.win_rate() * 100.);
+    for (outcome, count) in summary.outcome_counts() {
+        let mean_turns = summary.mean_turns(outcome).unwrap_or(0.);
+        println!("{:?}: {} (mean {:.0} turns)", outcome, count, mean_turns);
+    }
+}
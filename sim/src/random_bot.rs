@@ -0,0 +1,53 @@
+use crate::{Bot, View};
+use boat_journey_game::{CardinalDirection, Coord, Input, Menu, MenuChoice, Npc};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_isaac::Isaac64Rng;
+
+/// Presses random keys. Useful as a baseline and for finding crashes.
+pub struct RandomBot {
+    rng: Isaac64Rng,
+}
+
+impl RandomBot {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Isaac64Rng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Bot for RandomBot {
+    fn act(&mut self, view: View) -> Input {
+        if !view.is_driving() && view.is_player_on_boat() && self.rng.gen_bool(0.5) {
+            return Input::DriveToggle;
+        }
+        match self.rng.gen_range(0..20) {
+            0 => Input::Wait,
+            1 => Input::DriveToggle,
+            2 => Input::Ability(
+                self.rng
+                    .gen_range(1..=(view.passengers().len().max(1) as u8)),
+            ),
+            _ => {
+                use CardinalDirection::*;
+                Input::Walk(*[North, East, South, West].choose(&mut self.rng).unwrap())
+            }
+        }
+    }
+
+    fn choose(&mut self, _view: View, menu: &Menu) -> Option<MenuChoice> {
+        // Never deliberately give up the run
+        let choices = menu
+            .choices
+            .iter()
+            .filter(|c| !matches!(c, MenuChoice::StayAtInnForever | MenuChoice::AbandonQuest))
+            .cloned()
+            .collect::<Vec<_>>();
+        choices.choose(&mut self.rng).cloned()
+    }
+
+    fn aim(&mut self, view: View, _npc: Npc) -> Option<Coord> {
+        let offset = Coord::new(self.rng.gen_range(-8..=8), self.rng.gen_range(-8..=8));
+        Some(view.player_coord() + offset)
+    }
+}
@@ -1,20 +1,20 @@
-use boat_journey_app::{app, AppArgs, AppStorage, InitialRngSeed};
+use boat_journey_app::{app, AppArgs, AppStorage, InitialRngSeed, StorageHandle};
 use chargrid_web::{Context, Size};
-use general_storage_static::StaticStorage;
+use general_storage_static::Storage;
 use general_storage_web::LocalStorage;
 use wasm_bindgen::prelude::*;
 
 const SAVE_KEY: &str = "save";
 const CONFIG_KEY: &str = "config";
 const CONTROLS_KEY: &str = "controls";
+const REPLAY_KEY: &str = "replay";
 
 #[wasm_bindgen(start)]
 pub fn run() -> Result<(), JsValue> {
     wasm_logger::init(wasm_logger::Config::new(log::Level::Info));
     console_error_panic_hook::set_once();
-    let mut storage = StaticStorage::new(LocalStorage::new());
+    let mut storage = StorageHandle::new(LocalStorage::new());
     let _ = storage.remove(CONFIG_KEY);
-    let _ = storage.remove(CONTROLS_KEY);
     let context = Context::new(Size::new(80, 60), "content");
     let args = AppArgs {
         storage: AppStorage {
@@ -22,9 +22,11 @@ pub fn run() -> Result<(), JsValue> {
             save_game_key: SAVE_KEY.to_string(),
             config_key: CONFIG_KEY.to_string(),
             controls_key: CONTROLS_KEY.to_string(),
+            replay_key: REPLAY_KEY.to_string(),
         },
         initial_rng_seed: InitialRngSeed::Random,
         omniscient: false,
+        debug: false,
         new_game: false,
     };
     context.run(app(args));
@@ -32,6 +32,7 @@ fn main() {
                 storage,
                 initial_rng_seed,
                 omniscient,
+                debug,
                 new_game,
             },
         force_opengl,
@@ -63,6 +64,7 @@ fn main() {
         storage,
         initial_rng_seed,
         omniscient,
+        debug,
         new_game,
     }));
 }